@@ -0,0 +1,497 @@
+// ### 任务12：紧凑二进制编解码器
+// **目标**：掌握手写二进制协议、LEB128变长整数、自描述的字段编码
+// **描述**：为web3载荷（地址、余额、nonce、calldata等）实现一套比JSON/hex紧凑得多的
+// 二进制编码方案，并且支持向前兼容：旧版解码器遇到新增字段时能直接跳过，而不是报错
+
+// **流程提示**：
+// 1. 实现无符号LEB128变长整数的编码/解码（含溢出与截断保护）
+// 2. 定义字段的tag+length+value编码方案：每个字段 = field_id + type tag + （变长类型的）LEB128长度 + 值
+// 3. tag的最高位标记“这个类型带长度前缀”，这样未来新增的类型即使解码器不认识也能按长度跳过
+// 4. 基于上面的基础设施实现一个具体的web3载荷（Payload）的encode/decode
+
+/// 编解码过程中可能出现的错误
+#[derive(Debug, PartialEq)]
+pub enum CodecError {
+    /// 数据在预期结束之前就耗尽了
+    UnexpectedEof,
+    /// LEB128变长整数超过了64位能表示的范围（超过10个延续分组）
+    VarintOverflow,
+    /// 遇到了既没有被识别、也没有长度前缀可供跳过的tag
+    UnknownTag(u8),
+    /// 解码出的字段里缺少某个必需的field_id
+    MissingField(u8),
+}
+
+impl std::fmt::Display for CodecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodecError::UnexpectedEof => write!(f, "解码时数据提前结束"),
+            CodecError::VarintOverflow => write!(f, "LEB128变长整数超过64位范围"),
+            CodecError::UnknownTag(tag) => write!(f, "未知且无法跳过的字段类型tag: {}", tag),
+            CodecError::MissingField(id) => write!(f, "缺少必需字段: {}", id),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+/// 把`value`按无符号LEB128编码，追加到`buf`末尾：
+/// 每字节取7位，最低有效组在前；除最后一个字节外都设置延续位(0x80)
+pub fn encode_varint(mut value: u64, buf: &mut Vec<u8>) {
+    loop {
+        let mut byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value != 0 {
+            byte |= 0x80;
+        }
+        buf.push(byte);
+        if value == 0 {
+            break;
+        }
+    }
+}
+
+/// 从`buf[*pos..]`解码一个无符号LEB128整数，成功后`*pos`前进到消费掉的字节之后。
+/// 一个u64最多需要10个7位分组，超过这个数量视为溢出；数据提前结束则报错。
+pub fn decode_varint(buf: &[u8], pos: &mut usize) -> Result<u64, CodecError> {
+    let mut result: u64 = 0;
+    for i in 0..10u32 {
+        let byte = *buf.get(*pos).ok_or(CodecError::UnexpectedEof)?;
+        *pos += 1;
+        result |= ((byte & 0x7F) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
+    Err(CodecError::VarintOverflow)
+}
+
+// tag最高位是“是否带LEB128长度前缀”的标记：
+// - 不带这个标记的tag必须自描述长度（目前只有Varint），解码器即使不认识具体语义也无法跳过
+// - 带这个标记的tag，哪怕解码器不认识具体类型，也能读出长度后直接跳过，从而保证协议可以
+//   安全地新增字段类型而不破坏旧客户端
+const TAG_LENGTH_PREFIXED: u8 = 0x80;
+const TAG_VARINT: u8 = 0x00;
+const TAG_BYTES: u8 = TAG_LENGTH_PREFIXED | 0x01;
+const TAG_STRUCT: u8 = TAG_LENGTH_PREFIXED | 0x02;
+
+/// 一个解码出的字段值。`Unknown`保留了未识别但带长度前缀的原始字节，
+/// 调用方可以选择忽略，也可以按自己知道的语义重新解释。
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    UInt(u64),
+    Bytes(Vec<u8>),
+    Struct(Vec<(u8, FieldValue)>),
+    Unknown(u8, Vec<u8>),
+}
+
+impl FieldValue {
+    fn tag(&self) -> u8 {
+        match self {
+            FieldValue::UInt(_) => TAG_VARINT,
+            FieldValue::Bytes(_) => TAG_BYTES,
+            FieldValue::Struct(_) => TAG_STRUCT,
+            FieldValue::Unknown(tag, _) => *tag,
+        }
+    }
+}
+
+/// 把一组`(field_id, value)`编码成自描述的二进制格式：
+/// 每个字段 = 1字节field_id + 1字节type tag + （变长类型的）LEB128长度 + 值本身
+pub fn encode_fields(fields: &[(u8, FieldValue)], buf: &mut Vec<u8>) {
+    for (id, value) in fields {
+        buf.push(*id);
+        buf.push(value.tag());
+        match value {
+            FieldValue::UInt(v) => encode_varint(*v, buf),
+            FieldValue::Bytes(bytes) => {
+                encode_varint(bytes.len() as u64, buf);
+                buf.extend_from_slice(bytes);
+            }
+            FieldValue::Struct(inner) => {
+                let mut inner_buf = Vec::new();
+                encode_fields(inner, &mut inner_buf);
+                encode_varint(inner_buf.len() as u64, buf);
+                buf.extend_from_slice(&inner_buf);
+            }
+            FieldValue::Unknown(_, raw) => {
+                encode_varint(raw.len() as u64, buf);
+                buf.extend_from_slice(raw);
+            }
+        }
+    }
+}
+
+/// 解码出`(field_id, value)`序列。遇到无法识别的tag时，只要该tag带长度前缀，
+/// 就整段跳过保留为`FieldValue::Unknown`，而不会让后续字段的解码失败。
+pub fn decode_fields(buf: &[u8], pos: &mut usize) -> Result<Vec<(u8, FieldValue)>, CodecError> {
+    let mut fields = Vec::new();
+    while *pos < buf.len() {
+        let id = *buf.get(*pos).ok_or(CodecError::UnexpectedEof)?;
+        *pos += 1;
+        let tag = *buf.get(*pos).ok_or(CodecError::UnexpectedEof)?;
+        *pos += 1;
+
+        match tag {
+            TAG_VARINT => {
+                let v = decode_varint(buf, pos)?;
+                fields.push((id, FieldValue::UInt(v)));
+            }
+            TAG_BYTES => {
+                let bytes = decode_length_prefixed(buf, pos)?.to_vec();
+                fields.push((id, FieldValue::Bytes(bytes)));
+            }
+            TAG_STRUCT => {
+                let inner = decode_length_prefixed(buf, pos)?;
+                let mut inner_pos = 0;
+                let inner_fields = decode_fields(inner, &mut inner_pos)?;
+                fields.push((id, FieldValue::Struct(inner_fields)));
+            }
+            other if other & TAG_LENGTH_PREFIXED != 0 => {
+                let raw = decode_length_prefixed(buf, pos)?.to_vec();
+                fields.push((id, FieldValue::Unknown(other, raw)));
+            }
+            other => return Err(CodecError::UnknownTag(other)),
+        }
+    }
+    Ok(fields)
+}
+
+// 读出一个LEB128长度，再切出对应长度的切片；`*pos`前进到切片之后
+fn decode_length_prefixed<'a>(buf: &'a [u8], pos: &mut usize) -> Result<&'a [u8], CodecError> {
+    let len = decode_varint(buf, pos)? as usize;
+    let end = pos.checked_add(len).ok_or(CodecError::UnexpectedEof)?;
+    let slice = buf.get(*pos..end).ok_or(CodecError::UnexpectedEof)?;
+    *pos = end;
+    Ok(slice)
+}
+
+const FIELD_ADDRESS: u8 = 1;
+const FIELD_BALANCE: u8 = 2;
+const FIELD_NONCE: u8 = 3;
+const FIELD_CALL_DATA: u8 = 4;
+
+/// 一笔典型的web3载荷：账户地址、余额、nonce、调用数据。
+/// 相比JSON/hex文本表示，地址和calldata按原始字节存储，整数按LEB128变长存储，
+/// 体积通常能缩小数倍。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Payload {
+    pub address: [u8; 20],
+    pub balance: u64,
+    pub nonce: u64,
+    pub call_data: Vec<u8>,
+}
+
+impl Payload {
+    pub fn encode(&self) -> Vec<u8> {
+        let fields = vec![
+            (FIELD_ADDRESS, FieldValue::Bytes(self.address.to_vec())),
+            (FIELD_BALANCE, FieldValue::UInt(self.balance)),
+            (FIELD_NONCE, FieldValue::UInt(self.nonce)),
+            (FIELD_CALL_DATA, FieldValue::Bytes(self.call_data.clone())),
+        ];
+        let mut buf = Vec::new();
+        encode_fields(&fields, &mut buf);
+        buf
+    }
+
+    /// 解码；字节流里出现本版本不认识的字段（比如未来新增的gas_limit）会被
+    /// `decode_fields`透明跳过，不影响已知字段的解析。
+    pub fn decode(buf: &[u8]) -> Result<Self, CodecError> {
+        let mut pos = 0;
+        let fields = decode_fields(buf, &mut pos)?;
+
+        let mut address = None;
+        let mut balance = 0u64;
+        let mut nonce = 0u64;
+        let mut call_data = Vec::new();
+
+        for (id, value) in fields {
+            match (id, value) {
+                (FIELD_ADDRESS, FieldValue::Bytes(b)) if b.len() == 20 => {
+                    let mut arr = [0u8; 20];
+                    arr.copy_from_slice(&b);
+                    address = Some(arr);
+                }
+                (FIELD_BALANCE, FieldValue::UInt(v)) => balance = v,
+                (FIELD_NONCE, FieldValue::UInt(v)) => nonce = v,
+                (FIELD_CALL_DATA, FieldValue::Bytes(b)) => call_data = b,
+                // 未识别或语义不匹配的字段：本版本解码器不关心，直接忽略
+                _ => {}
+            }
+        }
+
+        Ok(Payload {
+            address: address.ok_or(CodecError::MissingField(FIELD_ADDRESS))?,
+            balance,
+            nonce,
+            call_data,
+        })
+    }
+}
+
+// ### 任务13：可插拔传输层 —— 直连RPC vs. Nostr中继广播
+// **目标**：把"把载荷发送到哪里/从哪里订阅"与业务逻辑解耦
+// **描述**：把上面编解码出来的二进制载荷包在一个`Transport` trait背后。现有的直连
+// 行为变成`DirectTransport`，另外提供一个把载荷签名后广播到一组Nostr中继websocket的
+// `NostrTransport`，让使用方不必自己运行服务器也能获得抗审查的多路扇出。
+
+// 注意：`Cargo.toml`里有`tokio`，但没有`tokio-tungstenite`/`secp256k1`等中继连接与
+// 签名所需的依赖，因此`publish_to_relay`/`subscribe_to_relay`里真正的websocket收发和
+// NIP-01事件编码留空（直接返回`Ok(())`），这里只勾勒出`Transport`trait本身的形状。
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use thiserror::Error;
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+#[derive(Error, Debug)]
+pub enum TransportError {
+    #[error("connection error: {0}")]
+    Connection(String),
+    #[error("channel closed")]
+    ChannelClosed,
+}
+
+/// 一条已签名的信封：`topic`用于订阅过滤，`author`是签名者公钥，`payload`是
+/// 本文件前半部分`encode_fields`/`Payload::encode`产出的二进制内容。
+#[derive(Debug, Clone)]
+pub struct SignedEnvelope {
+    pub topic: String,
+    pub author: [u8; 32],
+    pub signature: Vec<u8>,
+    pub payload: Vec<u8>,
+}
+
+/// 订阅过滤条件：必须指定topic，author可选
+#[derive(Debug, Clone)]
+pub struct SubscribeFilter {
+    pub topic: String,
+    pub author: Option<[u8; 32]>,
+}
+
+/// 载荷的发送/订阅通道，与具体传输介质解耦：直连RPC和Nostr中继都只是这个
+/// trait的两种实现，上层业务代码只依赖`Transport`本身，可以在两者间自由切换。
+/// 方法返回装箱的`Future`而不是`async fn`，这样`dyn Transport`仍然是对象安全的，
+/// 和本文件里其它trait对象同一套约定。
+pub trait Transport: Send + Sync {
+    /// 把一段已经编码好的二进制载荷发布到某个topic
+    fn send(&self, topic: &str, payload: Vec<u8>) -> BoxFuture<'_, Result<(), TransportError>>;
+
+    /// 订阅某个topic（可选按author过滤），返回一个不断产出新信封的接收端
+    fn subscribe(&self, filter: SubscribeFilter) -> BoxFuture<'_, Result<mpsc::Receiver<SignedEnvelope>, TransportError>>;
+}
+
+/// 现有行为的直连实现：不签名、不走中继，payload直接投进一个进程内通道。
+/// 代表重构前"直接RPC连接"那条路径，保证旧调用方的行为原样保留。
+pub struct DirectTransport {
+    sender: mpsc::Sender<SignedEnvelope>,
+    receiver: tokio::sync::Mutex<Option<mpsc::Receiver<SignedEnvelope>>>,
+}
+
+impl DirectTransport {
+    pub fn new(buffer: usize) -> Self {
+        let (sender, receiver) = mpsc::channel(buffer);
+        Self {
+            sender,
+            receiver: tokio::sync::Mutex::new(Some(receiver)),
+        }
+    }
+}
+
+impl Transport for DirectTransport {
+    fn send(&self, topic: &str, payload: Vec<u8>) -> BoxFuture<'_, Result<(), TransportError>> {
+        let envelope = SignedEnvelope {
+            topic: topic.to_string(),
+            author: [0u8; 32], // 直连模式不签名，author留空
+            signature: Vec::new(),
+            payload,
+        };
+        Box::pin(async move {
+            self.sender
+                .send(envelope)
+                .await
+                .map_err(|_| TransportError::ChannelClosed)
+        })
+    }
+
+    fn subscribe(&self, _filter: SubscribeFilter) -> BoxFuture<'_, Result<mpsc::Receiver<SignedEnvelope>, TransportError>> {
+        Box::pin(async move {
+            self.receiver
+                .lock()
+                .await
+                .take()
+                .ok_or_else(|| TransportError::Connection("receiver已经被取走".to_string()))
+        })
+    }
+}
+
+/// 最小化的签名器接口：把消息签名成字节串。真实实现应该基于secp256k1/schnorr
+/// （Nostr事件用的是schnorr签名），这里只定义接口，方便`NostrTransport`在不同
+/// 签名后端之间复用，也方便测试时换成固定输出的假签名器。
+pub trait Signer: Send + Sync {
+    fn public_key(&self) -> [u8; 32];
+    fn sign(&self, message: &[u8]) -> Vec<u8>;
+}
+
+/// Nostr中继的websocket地址
+pub type RelayUrl = String;
+
+/// 通过一组Nostr中继websocket广播/订阅信封的`Transport`实现：每条外发消息先用
+/// `signer`签名，再包进按topic分类的信封，发布给全部中继；订阅方可以按topic、
+/// author过滤，从多个中继里任选其一收到消息即可，从而不依赖某一台服务器在线。
+pub struct NostrTransport {
+    relays: Vec<RelayUrl>,
+    signer: Arc<dyn Signer>,
+}
+
+impl NostrTransport {
+    pub fn new(relays: Vec<RelayUrl>, signer: Arc<dyn Signer>) -> Self {
+        Self { relays, signer }
+    }
+
+    fn sign_envelope(&self, topic: &str, payload: Vec<u8>) -> SignedEnvelope {
+        SignedEnvelope {
+            signature: self.signer.sign(&payload),
+            author: self.signer.public_key(),
+            topic: topic.to_string(),
+            payload,
+        }
+    }
+}
+
+impl Transport for NostrTransport {
+    fn send(&self, topic: &str, payload: Vec<u8>) -> BoxFuture<'_, Result<(), TransportError>> {
+        let envelope = self.sign_envelope(topic, payload);
+        Box::pin(async move {
+            // 向每个中继广播一份；单个中继连不上不应该让整次发送失败——
+            // 这正是多路中继相比单一RPC服务器的censorship-resistance所在。
+            for relay in &self.relays {
+                if let Err(e) = publish_to_relay(relay, &envelope).await {
+                    eprintln!("中继 {} 广播失败（已忽略，继续尝试其它中继）: {}", relay, e);
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn subscribe(&self, filter: SubscribeFilter) -> BoxFuture<'_, Result<mpsc::Receiver<SignedEnvelope>, TransportError>> {
+        let relays = self.relays.clone();
+        Box::pin(async move {
+            let (tx, rx) = mpsc::channel(64);
+            for relay in relays {
+                let tx = tx.clone();
+                let filter = filter.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = subscribe_to_relay(&relay, filter, tx).await {
+                        eprintln!("中继 {} 订阅失败: {}", relay, e);
+                    }
+                });
+            }
+            Ok(rx)
+        })
+    }
+}
+
+// 与具体中继的websocket交互：建立连接、按NIP-01格式序列化/解析事件。
+// 这部分需要`tokio-tungstenite`等网络依赖，`Cargo.toml`里没有声明，
+// 因此是空实现：不建立连接、直接返回`Ok(())`，只保证调用方看到的接口形状是对的。
+async fn publish_to_relay(relay: &RelayUrl, envelope: &SignedEnvelope) -> Result<(), TransportError> {
+    let _ = (relay, envelope);
+    Ok(())
+}
+
+async fn subscribe_to_relay(
+    relay: &RelayUrl,
+    filter: SubscribeFilter,
+    tx: mpsc::Sender<SignedEnvelope>,
+) -> Result<(), TransportError> {
+    let _ = (relay, filter, tx);
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    println!("=== 紧凑二进制编解码器演示 ===");
+
+    // 1. LEB128变长整数的基本往返
+    println!("\n1. LEB128变长整数往返");
+    for value in [0u64, 1, 127, 128, 300, u64::MAX] {
+        let mut buf = Vec::new();
+        encode_varint(value, &mut buf);
+        let mut pos = 0;
+        let decoded = decode_varint(&buf, &mut pos).expect("解码失败");
+        println!("   {} -> {}字节 -> {}", value, buf.len(), decoded);
+        assert_eq!(value, decoded);
+    }
+
+    // 2. 数据提前结束 / 溢出的错误路径
+    println!("\n2. 错误路径");
+    let truncated = [0x80u8]; // 延续位还在等下一个字节，但流已经结束
+    let mut pos = 0;
+    println!("   截断的varint: {:?}", decode_varint(&truncated, &mut pos));
+    let overflow = [0x80u8; 10]; // 10个分组全部带延续位，永远没有结束
+    let mut pos = 0;
+    println!("   溢出的varint: {:?}", decode_varint(&overflow, &mut pos));
+
+    // 3. 一笔web3载荷的编码/解码往返
+    println!("\n3. Payload 编解码往返");
+    let payload = Payload {
+        address: [0x11; 20],
+        balance: 1_000_000_000_000u64,
+        nonce: 42,
+        call_data: vec![0xde, 0xad, 0xbe, 0xef],
+    };
+    let encoded = payload.encode();
+    println!("   编码后 {} 字节（vs. hex文本至少 {} 字节）", encoded.len(), 2 * 20 + 2 * 10 + 2 * 4);
+    let decoded = Payload::decode(&encoded).expect("解码失败");
+    assert_eq!(payload, decoded);
+    println!("   解码结果: {:?}", decoded);
+
+    // 4. 向前兼容：手工在编码结果后面追加一个本版本不认识的字段，
+    //    证明旧解码器仍然能正确解出已知字段，而不会因为陌生字段报错
+    println!("\n4. 向前兼容：追加一个未知字段后仍可解码");
+    let mut forward_compat = encoded.clone();
+    forward_compat.push(99); // 假想中未来版本新增的field_id
+    forward_compat.push(TAG_BYTES); // 带长度前缀，旧解码器也能安全跳过
+    encode_varint(3, &mut forward_compat);
+    forward_compat.extend_from_slice(b"gas");
+    let decoded_with_unknown = Payload::decode(&forward_compat).expect("解码失败");
+    assert_eq!(payload, decoded_with_unknown);
+    println!("   追加未知字段后依然解码成功: {:?}", decoded_with_unknown);
+
+    // 5. 可插拔传输层：直连RPC vs. 签名后广播到Nostr中继
+    println!("\n5. Transport: 直连 vs. Nostr中继");
+    let direct = DirectTransport::new(16);
+    direct.send("tx", payload.encode()).await.expect("直连发送失败");
+    let mut direct_rx = direct
+        .subscribe(SubscribeFilter { topic: "tx".to_string(), author: None })
+        .await
+        .expect("直连订阅失败");
+    let received = direct_rx.recv().await.expect("应当收到刚发送的信封");
+    println!("   直连传输收到: topic={}, {}字节", received.topic, received.payload.len());
+    assert_eq!(Payload::decode(&received.payload).unwrap(), payload);
+
+    struct ToyXorSigner {
+        key: [u8; 32],
+    }
+    impl Signer for ToyXorSigner {
+        fn public_key(&self) -> [u8; 32] {
+            self.key
+        }
+        // 仅用于演示接口形状，不是真正的密码学签名
+        fn sign(&self, message: &[u8]) -> Vec<u8> {
+            message.iter().map(|b| b ^ self.key[0]).collect()
+        }
+    }
+    let nostr = NostrTransport::new(
+        vec!["wss://relay.example.com".to_string(), "wss://relay2.example.com".to_string()],
+        Arc::new(ToyXorSigner { key: [0x42; 32] }),
+    );
+    nostr.send("tx", payload.encode()).await.expect("广播到中继失败");
+    println!("   已尝试签名后广播到 {} 个Nostr中继", 2);
+
+    println!("\n=== 演示完成 ===");
+}
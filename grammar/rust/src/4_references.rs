@@ -7,7 +7,7 @@ use std::sync::{Arc, Mutex};
 use std::collections::HashMap;
 
 // 定义结构体用于演示
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 struct Person {
     name: String,
     age: u32,
@@ -253,6 +253,208 @@ fn smart_pointers_demo() {
     println!("共享数据: {:?}", shared_data.borrow());
 }
 
+// Rc 循环引用演示：展示朴素的 Rc-only 循环如何泄漏内存，
+// 以及用 Weak 打破循环后内存如何被正常回收。
+fn reference_cycle_demo() {
+    use std::rc::{Rc, Weak};
+
+    println!("\n=== Rc 循环引用演示 ===");
+
+    // --- 朴素 Rc 循环：两个节点互相持有对方的强引用 ---
+    println!("--- 朴素 Rc 循环（会泄漏） ---");
+    {
+        struct LeakyNode {
+            name: String,
+            other: RefCell<Option<Rc<LeakyNode>>>,
+        }
+
+        impl Drop for LeakyNode {
+            fn drop(&mut self) {
+                println!("LeakyNode {} 被销毁", self.name);
+            }
+        }
+
+        let a = Rc::new(LeakyNode { name: "A".to_string(), other: RefCell::new(None) });
+        let b = Rc::new(LeakyNode { name: "B".to_string(), other: RefCell::new(None) });
+        *a.other.borrow_mut() = Some(Rc::clone(&b));
+        *b.other.borrow_mut() = Some(Rc::clone(&a));
+
+        println!("a 的强引用计数: {}", Rc::strong_count(&a));
+        println!("b 的强引用计数: {}", Rc::strong_count(&b));
+        // a、b 离开作用域时各自还被对方的 Rc 持有一份，
+        // 强引用计数停在 1，Drop 不会运行，内存泄漏。
+    }
+    println!("作用域已结束，但上面两个 LeakyNode 的 Drop 都没有打印 —— 内存泄漏了");
+
+    // --- 用 Weak 打破循环：子节点用 Rc 持有父节点用 Weak 回指 ---
+    println!("\n--- Weak 修复循环 ---");
+
+    struct Node {
+        name: String,
+        parent: RefCell<Weak<Node>>,
+        children: RefCell<Vec<Rc<Node>>>,
+    }
+
+    impl Drop for Node {
+        fn drop(&mut self) {
+            println!("Node {} 被销毁", self.name);
+        }
+    }
+
+    let leaf = Rc::new(Node {
+        name: "leaf".to_string(),
+        parent: RefCell::new(Weak::new()),
+        children: RefCell::new(vec![]),
+    });
+
+    println!("leaf: strong = {}, weak = {}", Rc::strong_count(&leaf), Rc::weak_count(&leaf));
+
+    {
+        let branch = Rc::new(Node {
+            name: "branch".to_string(),
+            parent: RefCell::new(Weak::new()),
+            children: RefCell::new(vec![Rc::clone(&leaf)]),
+        });
+
+        *leaf.parent.borrow_mut() = Rc::downgrade(&branch);
+
+        println!("branch: strong = {}, weak = {}", Rc::strong_count(&branch), Rc::weak_count(&branch));
+        println!("leaf: strong = {}, weak = {}", Rc::strong_count(&leaf), Rc::weak_count(&leaf));
+
+        match leaf.parent.borrow().upgrade() {
+            Some(parent) => println!("leaf 通过 upgrade() 访问到父节点: {}", parent.name),
+            None => println!("父节点已被销毁"),
+        }
+    }
+    // branch 离开作用域后被正常 Drop（这里会打印），因为 leaf
+    // 只持有 Weak，不会阻止 branch 的强引用计数降到 0。
+
+    println!("branch 离开作用域后，leaf: strong = {}, weak = {}", Rc::strong_count(&leaf), Rc::weak_count(&leaf));
+    match leaf.parent.borrow().upgrade() {
+        Some(parent) => println!("父节点仍然存活: {}", parent.name),
+        None => println!("父节点已被释放，upgrade() 返回 None"),
+    };
+}
+
+// 自定义智能指针 MyBox<T>：演示 Deref/DerefMut 的实现方式，
+// 以及解引用强制转换（deref coercion）是如何在函数调用处生效的。
+fn my_box_demo() {
+    use std::ops::{Deref, DerefMut};
+
+    println!("\n=== 自定义智能指针 MyBox<T> 演示 ===");
+
+    struct MyBox<T>(T);
+
+    impl<T> MyBox<T> {
+        fn new(x: T) -> MyBox<T> {
+            MyBox(x)
+        }
+    }
+
+    impl<T> Deref for MyBox<T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            &self.0
+        }
+    }
+
+    impl<T> DerefMut for MyBox<T> {
+        fn deref_mut(&mut self) -> &mut T {
+            &mut self.0
+        }
+    }
+
+    impl<T> Drop for MyBox<T> {
+        fn drop(&mut self) {
+            println!("MyBox 被销毁");
+        }
+    }
+
+    {
+        let m = MyBox::new(5);
+        println!("*m = {}", *m); // 触发 Deref::deref
+
+        let mut n = MyBox::new(10);
+        *n += 1; // 触发 DerefMut::deref_mut
+        println!("*n = {}", *n);
+    }
+
+    fn hello(name: &str) {
+        println!("你好, {}!", name);
+    }
+
+    // 解引用强制转换：&MyBox<String> -> &String -> &str，
+    // 编译器在函数调用处自动插入这两步解引用。
+    let m = MyBox::new(String::from("张三"));
+    hello(&m);
+
+    // 不依赖强制转换时需要手写完整的解引用链，例如对一层间接
+    // 引用 &MyBox<String> 要写 &(*(*m))[..]；这里 m 本身就是
+    // MyBox<String>，只需一层 *m 取出 String 再切片成 &str：
+    hello(&(*m)[..]);
+    println!("强制转换省去了手写解引用链和切片语法的麻烦，编译期零开销完成。");
+}
+
+// RefCell 的运行时借用检查：通过 Messenger trait + Mock 实现演示内部可变性，
+// 并展示违反借用规则时 RefCell 是在运行时 panic，而不是在编译期报错。
+fn refcell_messenger_demo() {
+    println!("\n=== RefCell 运行时借用检查演示 ===");
+
+    trait Messenger {
+        fn send(&self, msg: &str);
+    }
+
+    struct MockMessenger {
+        sent: RefCell<Vec<String>>,
+    }
+
+    impl MockMessenger {
+        fn new() -> Self {
+            MockMessenger { sent: RefCell::new(vec![]) }
+        }
+    }
+
+    impl Messenger for MockMessenger {
+        fn send(&self, msg: &str) {
+            // &self 是不可变的，但借助 RefCell 仍能在内部修改状态。
+            self.sent.borrow_mut().push(msg.to_string());
+        }
+    }
+
+    let messenger = MockMessenger::new();
+    messenger.send("hello");
+    messenger.send("world");
+    println!("通过 &self 记录下的消息: {:?}", messenger.sent.borrow());
+
+    // 借用规则在这里是运行时强制的，而不是编译期强制的 ——
+    // 对比 mutable_borrowing_demo 里那些注释掉的编译期错误示例。
+    println!("\n--- 故意同时持有两个 borrow_mut() ---");
+    // messenger.sent 是 RefCell（内部可变性），默认不是 UnwindSafe；
+    // 这里只是故意触发一次 panic 来观察 RefCell 的运行时借用检查，
+    // 并不依赖 panic 后 RefCell 内部状态的一致性，所以用 AssertUnwindSafe 绕过检查是安全的。
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let _first = messenger.sent.borrow_mut();
+        let _second = messenger.sent.borrow_mut(); // 运行时 panic: already borrowed
+    }));
+    match result {
+        Ok(_) => println!("未发生 panic（不应该到这里）"),
+        Err(_) => println!("捕获到 panic：RefCell 在运行时检测到了违反借用规则的行为"),
+    }
+
+    // 非 panic 的替代方案：try_borrow / try_borrow_mut 返回 Result 而不是 panic。
+    println!("\n--- try_borrow / try_borrow_mut ---");
+    let _guard = messenger.sent.borrow_mut();
+    match messenger.sent.try_borrow() {
+        Ok(_) => println!("try_borrow 成功（不应该到这里，因为已存在可变借用）"),
+        Err(e) => println!("try_borrow 返回 Err: {}", e),
+    }
+    match messenger.sent.try_borrow_mut() {
+        Ok(_) => println!("try_borrow_mut 成功（不应该到这里）"),
+        Err(e) => println!("try_borrow_mut 返回 Err: {}", e),
+    };
+}
+
 // 并发安全的智能指针
 fn concurrent_smart_pointers_demo() {
     println!("\n=== 并发安全智能指针演示 ===");
@@ -305,6 +507,88 @@ fn borrowing_patterns_demo() {
     println!("分割借用修改后: {:?}", data);
 }
 
+// 通过 &mut 移出非 Copy 值的限制，以及三种零拷贝的解决方式
+fn move_out_of_mut_demo() {
+    println!("\n=== 通过 &mut 移出值演示 ===");
+
+    // 编译错误示例：不能通过 &mut 引用移出非 Copy 值。
+    // fn take_out(item: &mut Option<Person>) -> Option<Person> {
+    //     return *item; // error[E0507]: cannot move out of `*item` which is
+    //                   // behind a mutable reference
+    // }
+
+    // 方案一：std::mem::replace —— 换入新值，换出旧值，零拷贝。
+    fn replace_person(item: &mut Option<Person>, new: Option<Person>) -> Option<Person> {
+        std::mem::replace(item, new)
+    }
+
+    let mut slot = Some(Person::new("张三".to_string(), 30));
+    println!("replace 前: {:?}", slot);
+    let old = replace_person(&mut slot, Some(Person::new("李四".to_string(), 25)));
+    println!("replace 后: slot = {:?}, 换出的旧值 = {:?}", slot, old);
+
+    // 方案二：std::mem::take —— 对实现 Default 的类型，换入 T::default()。
+    fn take_person(item: &mut Option<Person>) -> Option<Person> {
+        std::mem::take(item)
+    }
+
+    let mut slot2 = Some(Person::new("王五".to_string(), 40));
+    println!("\ntake 前: {:?}", slot2);
+    let taken = take_person(&mut slot2);
+    println!("take 后: slot2 = {:?}（Option 的 Default 是 None）, 取出的值 = {:?}", slot2, taken);
+
+    // 方案三：std::mem::swap —— 交换两个 &mut 目标，不需要克隆。
+    let mut a = Person::new("A".to_string(), 1);
+    let mut b = Person::new("B".to_string(), 2);
+    println!("\nswap 前: a = {:?}, b = {:?}", a, b);
+    std::mem::swap(&mut a, &mut b);
+    println!("swap 后: a = {:?}, b = {:?}", a, b);
+}
+
+// 对集合中不相交元素的安全同时可变借用，以及一个 NLL（非词法生命周期）示例
+fn disjoint_borrows_demo() {
+    println!("\n=== 不相交借用演示 ===");
+
+    fn get_two_mut<T>(slice: &mut [T], i: usize, j: usize) -> (&mut T, &mut T) {
+        assert!(i != j, "i 和 j 必须不同，否则会产生两个指向同一元素的可变引用");
+
+        let (lo, hi) = if i < j { (i, j) } else { (j, i) };
+        let (left, right) = slice.split_at_mut(hi);
+        // left[lo] 借用自前半段，right[0]（原来的 hi 位置）借用自后半段，
+        // 两者此后生命周期互不重叠，借用检查器能够接受同时存在。
+        let (a, b) = (&mut left[lo], &mut right[0]);
+        if i < j { (a, b) } else { (b, a) }
+    }
+
+    let mut people = vec![
+        Person::new("张三".to_string(), 30),
+        Person::new("李四".to_string(), 25),
+        Person::new("王五".to_string(), 40),
+    ];
+
+    println!("交换前: {:?}", people);
+
+    // 朴素写法无法通过借用检查：编译器无法证明 &mut people[0] 和
+    // &mut people[2] 不重叠（它们都被当作借用了整个 people）。
+    // let (a, b) = (&mut people[0], &mut people[2]); // error[E0499]
+
+    {
+        let (a, b) = get_two_mut(&mut people, 0, 2);
+        std::mem::swap(a, b);
+        b.set_age(b.age + 1);
+    }
+    println!("交换并修改后: {:?}", people);
+
+    // 非词法生命周期（NLL）示例：不可变借用在最后一次使用处结束，
+    // 而不是持续到作用域末尾，所以紧随其后的可变借用是合法的。
+    let mut value = 10;
+    let r = &value; // 不可变借用开始
+    println!("读取: {}", r); // r 的最后一次使用，借用在这里结束（而非作用域末尾）
+    let m = &mut value; // 若借用延续到作用域末尾，这里会编译错误；NLL 下可以通过
+    *m += 1;
+    println!("修改后的 value: {}", value);
+}
+
 // 高级引用模式
 fn advanced_reference_patterns() {
     println!("\n=== 高级引用模式演示 ===");
@@ -424,6 +708,134 @@ fn cache_system_demo() {
     }
 }
 
+// LRU 缓存：在上面无界 Cache 的基础上加上容量限制和淘汰策略。
+// 用 HashMap<K, Rc<RefCell<Node>>> 做 O(1) 查找，配合一条侵入式双向
+// 链表维护访问顺序；链表的“前进”方向（next）用 Rc 强引用，
+// “后退”方向（prev）用 Weak，避免相邻两个节点互相强引用成环。
+fn lru_cache_demo() {
+    use std::rc::{Rc, Weak};
+    use std::hash::Hash;
+
+    println!("\n=== LRU 缓存演示 ===");
+
+    struct Node<K, V> {
+        key: K,
+        value: V,
+        prev: Option<Weak<RefCell<Node<K, V>>>>,
+        next: Option<Rc<RefCell<Node<K, V>>>>,
+    }
+
+    struct LruCache<K, V> {
+        capacity: usize,
+        map: HashMap<K, Rc<RefCell<Node<K, V>>>>,
+        head: Option<Rc<RefCell<Node<K, V>>>>, // 最近使用
+        tail: Option<Weak<RefCell<Node<K, V>>>>, // 最久未使用
+    }
+
+    impl<K: Eq + Hash + Clone + std::fmt::Debug, V: Clone> LruCache<K, V> {
+        fn new(capacity: usize) -> Self {
+            LruCache { capacity, map: HashMap::new(), head: None, tail: None }
+        }
+
+        // 把 node 从链表中摘下，但保留在 map 中（调用方决定是否继续使用它）。
+        fn detach(&mut self, node: &Rc<RefCell<Node<K, V>>>) {
+            let prev = node.borrow().prev.clone();
+            let next = node.borrow().next.clone();
+
+            match (prev.as_ref().and_then(Weak::upgrade), next.as_ref()) {
+                (Some(prev_rc), Some(next_rc)) => {
+                    prev_rc.borrow_mut().next = Some(Rc::clone(next_rc));
+                    next_rc.borrow_mut().prev = Some(Rc::downgrade(&prev_rc));
+                }
+                (Some(prev_rc), None) => {
+                    prev_rc.borrow_mut().next = None;
+                    self.tail = Some(Rc::downgrade(&prev_rc));
+                }
+                (None, Some(next_rc)) => {
+                    next_rc.borrow_mut().prev = None;
+                    self.head = Some(Rc::clone(next_rc));
+                }
+                (None, None) => {
+                    self.head = None;
+                    self.tail = None;
+                }
+            }
+
+            node.borrow_mut().prev = None;
+            node.borrow_mut().next = None;
+        }
+
+        // 把 node 接到链表最前面（最近使用的位置）。
+        fn push_front(&mut self, node: Rc<RefCell<Node<K, V>>>) {
+            match self.head.take() {
+                Some(old_head) => {
+                    old_head.borrow_mut().prev = Some(Rc::downgrade(&node));
+                    node.borrow_mut().next = Some(old_head);
+                    self.head = Some(Rc::clone(&node));
+                }
+                None => {
+                    self.tail = Some(Rc::downgrade(&node));
+                    self.head = Some(Rc::clone(&node));
+                }
+            }
+        }
+
+        fn get(&mut self, key: &K) -> Option<V> {
+            let node = self.map.get(key)?.clone();
+            self.detach(&node);
+            self.push_front(Rc::clone(&node));
+            let value = node.borrow().value.clone();
+            Some(value)
+        }
+
+        fn put(&mut self, key: K, value: V) {
+            if let Some(existing) = self.map.get(&key).cloned() {
+                existing.borrow_mut().value = value;
+                self.detach(&existing);
+                self.push_front(existing);
+                return;
+            }
+
+            let node = Rc::new(RefCell::new(Node {
+                key: key.clone(),
+                value,
+                prev: None,
+                next: None,
+            }));
+            self.map.insert(key, Rc::clone(&node));
+            self.push_front(node);
+
+            if self.map.len() > self.capacity {
+                if let Some(tail_rc) = self.tail.as_ref().and_then(Weak::upgrade) {
+                    let evicted_key = tail_rc.borrow().key.clone();
+                    self.detach(&tail_rc);
+                    self.map.remove(&evicted_key);
+                    println!("LRU 淘汰: {:?}", evicted_key);
+                }
+            }
+        }
+    }
+
+    let mut lru: LruCache<&str, i32> = LruCache::new(3);
+    lru.put("a", 1);
+    lru.put("b", 2);
+    lru.put("c", 3);
+    println!("插入 a, b, c（容量 3）");
+
+    lru.get(&"a"); // a 变为最近使用，淘汰顺序现在是 b -> c -> a
+    println!("访问 a 后，a 被移到最前面");
+
+    lru.put("d", 4); // 触发淘汰，b 是最久未使用的，应该被淘汰
+    println!("插入 d，触发淘汰");
+
+    for key in ["a", "b", "c", "d"] {
+        match lru.get(&key) {
+            Some(value) => println!("{} 仍在缓存中，值为 {}", key, value),
+            None => println!("{} 已被淘汰", key),
+        }
+    }
+}
+
 fn main() {
     println!("=== Rust 引用和借用学习 ===");
     
@@ -444,13 +856,28 @@ fn main() {
     
     // 6. 智能指针
     smart_pointers_demo();
-    
+
+    // 6.1 Rc 循环引用与 Weak 修复
+    reference_cycle_demo();
+
+    // 6.2 自定义智能指针 MyBox<T>
+    my_box_demo();
+
+    // 6.3 RefCell 运行时借用检查
+    refcell_messenger_demo();
+
     // 7. 并发安全智能指针
     concurrent_smart_pointers_demo();
     
     // 8. 借用模式
     borrowing_patterns_demo();
-    
+
+    // 8.1 通过 &mut 移出值
+    move_out_of_mut_demo();
+
+    // 8.2 不相交借用与 NLL
+    disjoint_borrows_demo();
+
     // 9. 高级引用模式
     advanced_reference_patterns();
     
@@ -459,7 +886,10 @@ fn main() {
     
     // 11. 实际应用示例
     cache_system_demo();
-    
+
+    // 11.1 LRU 缓存
+    lru_cache_demo();
+
     println!("\n=== Rust 借用规则总结 ===");
     println!("1. 在任意给定时间，要么只能有一个可变引用，要么只能有多个不可变引用");
     println!("2. 引用必须总是有效的（不能有悬垂引用）");
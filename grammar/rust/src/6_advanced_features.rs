@@ -350,7 +350,7 @@ fn generics_examples() {
 // ============= 6. 错误处理 =============
 
 use std::fs::File;
-use std::io::{self, Read};
+use std::io::{self, BufRead, Read};
 
 fn error_handling_basics() {
     println!("\n=== 错误处理基础 ===");
@@ -405,6 +405,96 @@ impl From<std::num::ParseIntError> for MyError {
     }
 }
 
+impl std::fmt::Display for MyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MyError::Io(error) => write!(f, "IO错误: {}", error),
+            MyError::Parse(error) => write!(f, "解析错误: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for MyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MyError::Io(error) => Some(error),
+            MyError::Parse(error) => Some(error),
+        }
+    }
+}
+
+// 如果文件不存在就创建它并写入默认内容，其他打开失败原因照常向上传播
+fn open_or_create(path: &str, default_contents: &str) -> Result<File, MyError> {
+    match File::open(path) {
+        Ok(file) => Ok(file),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            std::fs::write(path, default_contents)?;
+            Ok(File::open(path)?)
+        }
+        Err(error) => Err(error.into()),
+    }
+}
+
+// 打开文件、按行读取、把每一行解析成整数再求和——From<io::Error>和From<ParseIntError>
+// 都会在这一个函数里通过?被用到
+fn load_and_sum(path: &str) -> Result<i32, MyError> {
+    let file = open_or_create(path, "0\n")?;
+    let reader = io::BufReader::new(file);
+
+    let mut sum = 0;
+    for line in reader.lines() {
+        let line = line?; // io::Error -> MyError::Io
+        if line.trim().is_empty() {
+            continue;
+        }
+        sum += line.trim().parse::<i32>()?; // ParseIntError -> MyError::Parse
+    }
+    Ok(sum)
+}
+
+#[cfg(test)]
+mod my_error_tests {
+    use super::*;
+
+    #[test]
+    fn missing_file_gets_created_with_default_contents() {
+        let path = std::env::temp_dir().join(format!("my_error_missing_{}.txt", std::process::id()));
+        let path = path.to_str().unwrap();
+        let _ = std::fs::remove_file(path);
+
+        let sum = load_and_sum(path).expect("应当自动创建文件并求和成功");
+        assert_eq!(sum, 0); // 默认内容是"0\n"
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn parse_failure_surfaces_as_my_error_parse() {
+        let path = std::env::temp_dir().join(format!("my_error_badparse_{}.txt", std::process::id()));
+        let path = path.to_str().unwrap();
+        std::fs::write(path, "1\nnot-a-number\n3\n").unwrap();
+
+        match load_and_sum(path) {
+            Err(MyError::Parse(_)) => {}
+            other => panic!("期望MyError::Parse，得到{:?}", other),
+        }
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn successful_sum_of_existing_file() {
+        let path = std::env::temp_dir().join(format!("my_error_sum_{}.txt", std::process::id()));
+        let path = path.to_str().unwrap();
+        std::fs::write(path, "1\n2\n3\n").unwrap();
+
+        let sum = load_and_sum(path).expect("应当求和成功");
+        assert_eq!(sum, 6);
+
+        std::fs::remove_file(path).ok();
+    }
+}
+
 // ============= 7. 宏 (Macros) =============
 
 // 声明式宏
@@ -429,22 +519,40 @@ macro_rules! hashmap {
     }}
 }
 
-// 过程宏示例（需要单独的 crate）
-// #[derive(Debug)]
-// struct MyStruct;
+// 过程宏示例：derive(Summary)来自独立的summary_derive crate（过程宏必须放在
+// proc-macro = true的独立crate里，见grammar/rust/summary_derive/src/lib.rs）。
+// 在这个crate的Cargo.toml里把它加为依赖后，#[derive(Summary)]就会为下面的结构体
+// 生成一份summarize()实现，和NewsArticle/Tweet手写的impl Summary共存。
+//
+//   [dependencies]
+//   summary_derive = { path = "summary_derive" }
+use summary_derive::Summary;
+
+#[derive(Summary)]
+struct BlogPost {
+    title: String,
+    body: String,
+}
 
 fn macro_examples() {
     println!("\n=== 宏示例 ===");
-    
+
     let v = vec![1, 2, 3];
     println!("使用自定义 vec! 宏: {:?}", v);
-    
+
     let map = hashmap!{
         "one" => 1,
         "two" => 2,
         "three" => 3
     };
     println!("使用 hashmap! 宏: {:?}", map);
+
+    // derive(Summary)生成的summarize()：取第一个String字段（这里是title）拼出摘要
+    let post = BlogPost {
+        title: String::from("Rust 1.0 发布"),
+        body: String::from("这是正文内容……"),
+    };
+    println!("derive(Summary)生成的摘要: {}", post.summarize());
 }
 
 // ============= 8. 并发 (Concurrency) =============
@@ -586,6 +694,304 @@ fn smart_pointers() {
     println!("modified value: {}", *value.borrow());
 }
 
+// ============= 9.1 双向链表：Rc<RefCell<Node<T>>> 下的共享所有权与内部可变性 =============
+//
+// smart_pointers()里Box/Rc/RefCell都是各自孤立演示的，这里把它们串起来做一个真正能用的
+// List<T>：每个节点被Rc共享（head/tail各持有一份），RefCell提供内部可变性以便在不拿到
+// &mut self的情况下修改节点的next/prev。pop时最容易出错的地方是prev/next循环引用——
+// 必须把取出节点两侧的链接都断开，它的Rc强引用计数才能真正归零，try_unwrap才会成功。
+mod linked_list {
+    use std::cell::{Ref, RefCell, RefMut};
+    use std::rc::Rc;
+
+    type Link<T> = Option<Rc<RefCell<Node<T>>>>;
+
+    struct Node<T> {
+        elem: T,
+        next: Link<T>,
+        prev: Link<T>,
+    }
+
+    impl<T> Node<T> {
+        fn new(elem: T) -> Rc<RefCell<Self>> {
+            Rc::new(RefCell::new(Node {
+                elem,
+                next: None,
+                prev: None,
+            }))
+        }
+    }
+
+    pub struct List<T> {
+        head: Link<T>,
+        tail: Link<T>,
+    }
+
+    impl<T> List<T> {
+        pub fn new() -> Self {
+            List { head: None, tail: None }
+        }
+
+        pub fn push_front(&mut self, elem: T) {
+            let new_node = Node::new(elem);
+            match self.head.take() {
+                Some(old_head) => {
+                    old_head.borrow_mut().prev = Some(new_node.clone());
+                    new_node.borrow_mut().next = Some(old_head);
+                    self.head = Some(new_node);
+                }
+                None => {
+                    self.tail = Some(new_node.clone());
+                    self.head = Some(new_node);
+                }
+            }
+        }
+
+        pub fn push_back(&mut self, elem: T) {
+            let new_node = Node::new(elem);
+            match self.tail.take() {
+                Some(old_tail) => {
+                    old_tail.borrow_mut().next = Some(new_node.clone());
+                    new_node.borrow_mut().prev = Some(old_tail);
+                    self.tail = Some(new_node);
+                }
+                None => {
+                    self.head = Some(new_node.clone());
+                    self.tail = Some(new_node);
+                }
+            }
+        }
+
+        pub fn pop_front(&mut self) -> Option<T> {
+            self.head.take().map(|old_head| {
+                match old_head.borrow_mut().next.take() {
+                    Some(new_head) => {
+                        // 断开新头朝旧头的prev，否则旧头的Rc强引用计数永远降不到1
+                        new_head.borrow_mut().prev = None;
+                        self.head = Some(new_head);
+                    }
+                    None => {
+                        self.tail.take();
+                    }
+                }
+                Rc::try_unwrap(old_head).ok().unwrap().into_inner().elem
+            })
+        }
+
+        pub fn pop_back(&mut self) -> Option<T> {
+            self.tail.take().map(|old_tail| {
+                match old_tail.borrow_mut().prev.take() {
+                    Some(new_tail) => {
+                        new_tail.borrow_mut().next = None;
+                        self.tail = Some(new_tail);
+                    }
+                    None => {
+                        self.head.take();
+                    }
+                }
+                Rc::try_unwrap(old_tail).ok().unwrap().into_inner().elem
+            })
+        }
+
+        // 借出元素而不克隆：Ref::map把"对Node的借用"投影成"对它elem字段的借用"
+        pub fn peek_front(&self) -> Option<Ref<'_, T>> {
+            self.head.as_ref().map(|node| Ref::map(node.borrow(), |n| &n.elem))
+        }
+
+        pub fn peek_back(&self) -> Option<Ref<'_, T>> {
+            self.tail.as_ref().map(|node| Ref::map(node.borrow(), |n| &n.elem))
+        }
+
+        pub fn peek_front_mut(&self) -> Option<RefMut<'_, T>> {
+            self.head
+                .as_ref()
+                .map(|node| RefMut::map(node.borrow_mut(), |n| &mut n.elem))
+        }
+
+        pub fn peek_back_mut(&self) -> Option<RefMut<'_, T>> {
+            self.tail
+                .as_ref()
+                .map(|node| RefMut::map(node.borrow_mut(), |n| &mut n.elem))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn mixed_front_back_operations_preserve_order() {
+            let mut list = List::new();
+            list.push_front(2);
+            list.push_front(1);
+            list.push_back(3);
+            list.push_back(4);
+            // 此时列表应为 1 2 3 4
+            assert_eq!(*list.peek_front().unwrap(), 1);
+            assert_eq!(*list.peek_back().unwrap(), 4);
+
+            assert_eq!(list.pop_front(), Some(1));
+            assert_eq!(list.pop_back(), Some(4));
+            assert_eq!(list.pop_front(), Some(2));
+            assert_eq!(list.pop_back(), Some(3));
+            assert_eq!(list.pop_front(), None);
+            assert_eq!(list.pop_back(), None);
+        }
+
+        #[test]
+        fn peek_mut_modifies_stored_element() {
+            let mut list = List::new();
+            list.push_back(1);
+            list.push_back(2);
+            *list.peek_back_mut().unwrap() += 10;
+            assert_eq!(list.pop_back(), Some(12));
+        }
+    }
+}
+
+fn linked_list_examples() {
+    println!("\n=== 双向链表 (Rc<RefCell<Node<T>>>) ===");
+
+    let mut list = linked_list::List::new();
+    list.push_back(1);
+    list.push_back(2);
+    list.push_front(0);
+
+    println!("front = {:?}", list.peek_front().as_deref());
+    println!("back = {:?}", list.peek_back().as_deref());
+
+    while let Some(value) = list.pop_front() {
+        println!("pop_front -> {}", value);
+    }
+}
+
+// ============= 9.2 树结构：用 Weak<T> 持有父节点，避免父子之间的引用循环 =============
+//
+// 父节点通过Rc拥有子节点（children: Vec<Rc<Node>>），如果子节点也用Rc指回父节点，
+// 就会形成一个Rc循环，双方的strong_count永远降不到0，内存永远不会被回收。
+// 解决办法是子节点只持有一个Weak<Node>：Weak不增加strong_count，
+// 需要访问父节点时调用upgrade()尝试获得一个Rc，父节点被真正drop后upgrade会返回None。
+mod tree {
+    use std::cell::RefCell;
+    use std::rc::{Rc, Weak};
+
+    pub struct Node {
+        pub value: i32,
+        pub parent: RefCell<Weak<Node>>,
+        pub children: RefCell<Vec<Rc<Node>>>,
+    }
+
+    impl Node {
+        // 创建一个暂时没有父节点的节点（根节点，或还未挂接到树上的节点）
+        pub fn new(value: i32) -> Rc<Node> {
+            Rc::new(Node {
+                value,
+                parent: RefCell::new(Weak::new()),
+                children: RefCell::new(Vec::new()),
+            })
+        }
+
+        // 创建一个新节点并立即挂接到parent下：parent.children拿到一份Rc（强引用，保证子节点存活），
+        // child.parent只存一份Weak（不增加parent的strong_count，避免循环）
+        pub fn new_child(value: i32, parent: &Rc<Node>) -> Rc<Node> {
+            let child = Node::new(value);
+            *child.parent.borrow_mut() = Rc::downgrade(parent);
+            parent.children.borrow_mut().push(Rc::clone(&child));
+            child
+        }
+
+        // 向上走一层：upgrade成功才说明父节点还活着，否则说明父节点已经被释放
+        pub fn parent(&self) -> Option<Rc<Node>> {
+            self.parent.borrow().upgrade()
+        }
+
+        pub fn strong_count(self: &Rc<Self>) -> usize {
+            Rc::strong_count(self)
+        }
+
+        pub fn weak_count(self: &Rc<Self>) -> usize {
+            Rc::weak_count(self)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn dropping_subtree_does_not_leak_and_parent_strong_count_is_restored() {
+            let leaf = Node::new(3);
+            assert!(leaf.parent().is_none());
+            // 此刻leaf只被这个局部变量持有
+            assert_eq!(Rc::strong_count(&leaf), 1);
+
+            {
+                let branch = Node::new(5);
+                // 把leaf挂接到branch下：branch.children持有一份额外的Rc<leaf>
+                branch.children.borrow_mut().push(Rc::clone(&leaf));
+                *leaf.parent.borrow_mut() = Rc::downgrade(&branch);
+
+                assert_eq!(Rc::strong_count(&leaf), 2); // 局部变量 + branch.children里的一份
+                assert_eq!(Rc::strong_count(&branch), 1);
+                assert_eq!(Rc::weak_count(&branch), 1); // leaf.parent里的那个Weak
+
+                assert!(leaf.parent().is_some());
+                // branch在这个作用域结束时被drop：它对leaf的强引用也随之释放
+            }
+
+            // branch已经被回收：leaf的strong_count回到挂接之前的1，leaf.parent()也拿不到upgrade结果了
+            assert_eq!(Rc::strong_count(&leaf), 1);
+            assert!(leaf.parent().is_none());
+        }
+
+        #[test]
+        fn new_child_wires_parent_and_children_both_ways() {
+            let root = Node::new(1);
+            let child = Node::new_child(2, &root);
+
+            assert_eq!(root.children.borrow().len(), 1);
+            assert_eq!(child.parent().unwrap().value, 1);
+        }
+    }
+}
+
+fn tree_examples() {
+    println!("\n=== 树结构 (Weak<Node> 父指针，避免引用循环) ===");
+
+    let leaf = tree::Node::new(3);
+    println!(
+        "leaf strong = {}, weak = {}",
+        leaf.strong_count(),
+        leaf.weak_count()
+    );
+
+    {
+        let branch = tree::Node::new(5);
+        branch.children.borrow_mut().push(Rc::clone(&leaf));
+        *leaf.parent.borrow_mut() = Rc::downgrade(&branch);
+
+        println!(
+            "branch strong = {}, weak = {}",
+            branch.strong_count(),
+            branch.weak_count()
+        );
+        println!(
+            "leaf strong = {}, weak = {}",
+            leaf.strong_count(),
+            leaf.weak_count()
+        );
+        println!("leaf 的父节点 value = {:?}", leaf.parent().map(|p| p.value));
+    }
+
+    // branch所在的作用域结束后已经被drop：leaf的strong_count回落，parent()也拿不到了
+    println!(
+        "leaf strong = {}, weak = {} (branch已超出作用域)",
+        leaf.strong_count(),
+        leaf.weak_count()
+    );
+    println!("leaf 的父节点 = {:?}", leaf.parent().map(|p| p.value));
+}
+
 // ============= 10. 模式匹配 =============
 
 fn pattern_matching() {
@@ -651,6 +1057,94 @@ fn pattern_matching() {
     }
 }
 
+// ============= 11. 切片 (Slices) =============
+//
+// 所有权/借用章节一直没有专门讲切片：&str和&[T]是"借用一段连续数据"的引用，
+// 这正是借用检查器发挥作用的地方——只要切片还活着，编译器就不允许修改它借用的源数据。
+mod slices {
+    // 找第一个单词：按字节扫描到第一个空格为止，返回该范围的字符串切片；
+    // 如果没有空格（只有一个单词），就返回整个字符串的切片
+    pub fn first_word(s: &str) -> &str {
+        let bytes = s.as_bytes();
+
+        for (i, &item) in bytes.iter().enumerate() {
+            if item == b' ' {
+                return &s[0..i];
+            }
+        }
+
+        &s[..]
+    }
+
+    // first_word的泛型版本：对任意切片取前n个元素，n超出长度时钳制到slice.len()，
+    // 避免和String切片越界时一样在字符边界/索引越界上panic
+    pub fn first_n<T>(slice: &[T], n: usize) -> &[T] {
+        &slice[..n.min(slice.len())]
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn first_word_stops_at_first_space() {
+            assert_eq!(first_word("hello world"), "hello");
+        }
+
+        #[test]
+        fn first_word_returns_whole_string_without_space() {
+            assert_eq!(first_word("hello"), "hello");
+        }
+
+        #[test]
+        fn first_word_handles_multibyte_utf8_without_splitting_a_codepoint() {
+            // "你好 世界"中文字符都是多字节UTF-8编码，空格前的"你好"整体作为切片边界，
+            // 不会落在某个字符编码的中间，所以按字节扫描是安全的
+            assert_eq!(first_word("你好 世界"), "你好");
+        }
+
+        #[test]
+        fn first_n_clamps_to_slice_length() {
+            let v = [1, 2, 3];
+            assert_eq!(first_n(&v, 2), &[1, 2]);
+            assert_eq!(first_n(&v, 10), &[1, 2, 3]); // 请求的n超出长度，钳制到slice.len()
+            assert_eq!(first_n(&v, 0), &[] as &[i32]);
+        }
+
+        // 这个测试本身就是"借用检查器拒绝同时存在的可变/不可变借用"的证明：
+        // first_word借出的切片仍然存活时，如果在下面加一行s.clear()，这个文件就无法通过编译——
+        // error[E0502]: cannot borrow `s` as mutable because it is also borrowed as immutable
+        #[test]
+        fn holding_slice_while_mutating_source_would_not_compile() {
+            let s = String::from("hello world");
+            let word = first_word(&s);
+            // s.clear(); // 取消这行注释会编译失败：word还借用着s，此时不能再可变借用s
+            assert_eq!(word, "hello");
+        }
+    }
+}
+
+fn slice_examples() {
+    println!("\n=== 切片 (&str / &[T]) ===");
+
+    let s = String::from("hello world");
+    let word = slices::first_word(&s);
+    println!("first_word(\"{}\") = \"{}\"", s, word);
+    // s.clear(); // 取消注释会编译失败：word还借用着s，借用检查器不允许此时再可变借用s
+    println!("word依然有效，因为上面这行mutation被借用检查器拒绝了: \"{}\"", word);
+
+    let greeting = "你好 世界";
+    println!(
+        "first_word(\"{}\") = \"{}\" (多字节UTF-8也不会被从中间切开)",
+        greeting,
+        slices::first_word(greeting)
+    );
+
+    let numbers = [10, 20, 30, 40, 50];
+    println!("first_n(&numbers, 3) = {:?}", slices::first_n(&numbers, 3));
+    println!("first_n(&numbers, 100) = {:?} (钳制到实际长度)", slices::first_n(&numbers, 100));
+}
+
 // ============= 主函数 =============
 
 fn main() {
@@ -678,7 +1172,12 @@ fn main() {
     
     // 6. 错误处理
     error_handling_basics();
-    
+    let numbers_path = std::env::temp_dir().join("6_advanced_features_numbers.txt");
+    match load_and_sum(numbers_path.to_str().unwrap()) {
+        Ok(sum) => println!("load_and_sum 求和结果: {}", sum),
+        Err(e) => println!("load_and_sum 失败: {}", e),
+    }
+
     // 7. 宏
     macro_examples();
     
@@ -690,9 +1189,14 @@ fn main() {
     
     // 9. 智能指针
     smart_pointers();
-    
+    linked_list_examples();
+    tree_examples();
+
     // 10. 模式匹配
     pattern_matching();
-    
+
+    // 11. 切片
+    slice_examples();
+
     println!("\n学习完成！");
 }
\ No newline at end of file
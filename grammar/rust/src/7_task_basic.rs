@@ -1,3 +1,8 @@
+// `PoolRef`实现了`std::alloc::Allocator`，只在开启`nightly-allocator` feature时
+// 编译——该trait仍在nightly的`allocator_api` feature背后，因此默认（stable）构建
+// 不启用它，只用稳定的`GlobalAlloc`适配器（见`PoolGlobalAlloc`）。
+#![cfg_attr(feature = "nightly-allocator", feature(allocator_api))]
+
 // ### 任务1：安全的内存管理器
 // **目标**：掌握所有权、借用、生命周期
 // **描述**：实现一个简单的内存池管理器，演示Rust的内存安全特性
@@ -12,14 +17,22 @@
 use std::time;
 use std::rc::Rc;
 use std::cell::RefCell;
+use std::collections::HashMap;
+#[cfg(feature = "nightly-allocator")]
+use std::alloc::{AllocError, Allocator};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::ptr::NonNull;
 
-// 内存块结构体
+// 内存块结构体：只做簿记（id/大小/状态/在哪个arena的哪个位置），
+// 实际数据由 `MemoryHandle` 直接指向所属arena的真实后备存储（见 `MemoryPool::allocate`）。
 #[derive(Debug)]
 struct MemoryBlock {
     id: usize,
-    size: usize,
+    size: usize, // 用户请求的原始大小（未按2的幂取整）
     is_free: bool,
-    data: Vec<u8>,
+    arena_index: usize,
+    offset: usize, // 在所属arena内的本地偏移
+    order: u32,    // 实际占用的伙伴块阶数，块大小为 2^order
 }
 
 // 并发日志系统测试演示
@@ -28,7 +41,7 @@ fn test_concurrent_logging() {
     
     // 1. 创建默认配置的日志系统
     println!("1. 创建日志系统");
-    let logger = Logger::new(LogConfig::default()).expect("创建日志系统失败");
+    let logger = Logger::new("demo", LogConfig::default()).expect("创建日志系统失败");
     
     // 2. 测试不同级别的日志
     println!("2. 测试不同级别的日志");
@@ -79,8 +92,17 @@ fn test_concurrent_logging() {
     let mut rotation_config = LogConfig::default();
     rotation_config.max_file_size = 100; // 很小的文件大小来触发轮转
     rotation_config.log_dir = "test_logs".to_string();
-    
-    let rotation_logger = Logger::new(rotation_config).expect("创建轮转日志系统失败");
+    rotation_config.sinks = vec![
+        SinkSpec::Stdout,
+        SinkSpec::RollingFile {
+            path: format!("{}/app.log", rotation_config.log_dir),
+            max_file_size: rotation_config.max_file_size,
+            max_files: rotation_config.max_files,
+        },
+    ];
+    rotation_config.flush_interval = Duration::from_millis(20); // 缩短兜底flush周期，让下面的短暂等待足以落盘
+
+    let rotation_logger = Logger::new("demo_rotation", rotation_config).expect("创建轮转日志系统失败");
     
     // 写入足够多的日志来触发轮转
     for i in 0..10 {
@@ -96,28 +118,37 @@ fn test_concurrent_logging() {
 }
 
 // 内存句柄 - 体现所有权转移和RAII
+//
+// `ptr`/`len`直接指向分配它的arena的真实后备存储（见`MemoryPool::allocate`），
+// 不再是一份和arena无关的`Vec<u8>`拷贝——这样写入的数据真的落在伙伴系统管理的
+// 那块内存上，而不是另一块临时分配出来、与arena毫无关联的缓冲区。
 pub struct MemoryHandle {
     id: usize,
-    data: Vec<u8>,
+    ptr: NonNull<u8>,
+    len: usize,
     pool: Rc<RefCell<MemoryPool>>,
 }
 
 impl MemoryHandle {
     // 获取内存数据的可变引用 - 体现借用规则
     pub fn data_mut(&mut self) -> &mut [u8] {
-        &mut self.data
+        // SAFETY: ptr指向本handle独占的arena后备存储区间；该arena由pool持有，
+        // 生命周期长于本handle（handle持有pool的Rc），且这段区间在handle被
+        // drop、块被释放之前不会被其他handle再次分配出去。
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
     }
-    
+
     // 获取内存数据的不可变引用
     pub fn data(&self) -> &[u8] {
-        &self.data
+        // SAFETY: 同`data_mut`
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
     }
-    
+
     // 获取内存块大小
     pub fn size(&self) -> usize {
-        self.data.len()
+        self.len
     }
-    
+
     // 获取内存块ID
     pub fn id(&self) -> usize {
         self.id
@@ -134,10 +165,154 @@ impl Drop for MemoryHandle {
     }
 }
 
-// 内存池结构体
+// 最小块阶数：块大小不会小于 2^MIN_ORDER 字节，避免极小请求把free list拆得过碎
+const MIN_ORDER: u32 = 4; // 16 字节
+
+// 把请求大小向上取整到 2 的幂，返回对应的阶数
+fn order_for_size(size: usize) -> u32 {
+    let size = size.max(1usize << MIN_ORDER);
+    let mut order = MIN_ORDER;
+    while (1usize << order) < size {
+        order += 1;
+    }
+    order
+}
+
+// arena后备存储的对齐：覆盖绝大多数容器/SIMD类型的对齐需求；
+// 请求对齐超过这个值时分配器会直接回退（见 `PoolGlobalAlloc`）
+const ARENA_ALIGN: usize = 4096;
+
+// 一个独立的伙伴系统内存区域：`free_lists[k]` 存放阶数为k、尚未分配的块
+// 在本arena内的本地偏移。每次 `add_block` 都会开辟一个这样的新arena，
+// 并真实地分配一段对齐到 `ARENA_ALIGN` 的后备存储，而不是每次分配时
+// 临时造一份 `vec![0; size]`——这样返回的指针才能真正喂给标准容器。
+#[derive(Debug)]
+struct Arena {
+    max_order: u32,
+    free_lists: Vec<Vec<usize>>,
+    storage: NonNull<u8>,
+    storage_layout: Layout,
+}
+
+impl Arena {
+    fn new(size: usize) -> Self {
+        let max_order = order_for_size(size);
+        let mut free_lists = vec![Vec::new(); (max_order + 1) as usize];
+        free_lists[max_order as usize].push(0); // 整个arena起初是一个满阶空闲块
+
+        let storage_layout = Layout::from_size_align(1usize << max_order, ARENA_ALIGN)
+            .expect("arena大小超出了系统可表示的最大布局");
+        // SAFETY: storage_layout大小恒大于0（MIN_ORDER保证至少16字节）
+        let storage = unsafe { std::alloc::alloc_zeroed(storage_layout) };
+        let storage =
+            NonNull::new(storage).unwrap_or_else(|| std::alloc::handle_alloc_error(storage_layout));
+
+        Self {
+            max_order,
+            free_lists,
+            storage,
+            storage_layout,
+        }
+    }
+
+    // 返回`offset`处后备存储的裸指针；调用方需保证offset在arena范围内
+    // 且没有其他存活的引用指向同一块内存
+    unsafe fn block_ptr(&self, offset: usize) -> NonNull<u8> {
+        unsafe { NonNull::new_unchecked(self.storage.as_ptr().add(offset)) }
+    }
+
+    // 判断一个指针是否落在本arena的后备存储范围内
+    fn owns(&self, ptr: NonNull<u8>) -> bool {
+        let base = self.storage.as_ptr() as usize;
+        let addr = ptr.as_ptr() as usize;
+        addr >= base && addr < base + self.storage_layout.size()
+    }
+
+    // 把一个已知属于本arena的指针换算回本地偏移
+    fn offset_of(&self, ptr: NonNull<u8>) -> usize {
+        ptr.as_ptr() as usize - self.storage.as_ptr() as usize
+    }
+
+    // 分配一个阶数为order的块；若该阶没有空闲块，则递归拆分更高阶的块，
+    // 每次拆分产生两个低一阶的伙伴，未使用的那一半挂回对应的free list
+    fn allocate_order(&mut self, order: u32) -> Option<usize> {
+        if order > self.max_order {
+            return None;
+        }
+        if let Some(offset) = self.free_lists[order as usize].pop() {
+            return Some(offset);
+        }
+        let parent_offset = self.allocate_order(order + 1)?;
+        let buddy_offset = parent_offset + (1 << order);
+        self.free_lists[order as usize].push(buddy_offset);
+        Some(parent_offset)
+    }
+
+    // 释放一个阶数为order、本地偏移为offset的块；沿着每一阶查找伙伴
+    // （offset异或块大小得到伙伴地址），能合并就一路合并到更高阶
+    fn free_order(&mut self, mut offset: usize, mut order: u32) {
+        while order < self.max_order {
+            let buddy = offset ^ (1 << order);
+            let list = &mut self.free_lists[order as usize];
+            match list.iter().position(|&o| o == buddy) {
+                Some(pos) => {
+                    list.remove(pos);
+                    offset = offset.min(buddy);
+                    order += 1;
+                }
+                None => break,
+            }
+        }
+        self.free_lists[order as usize].push(offset);
+    }
+
+    fn largest_free_block(&self) -> usize {
+        self.free_lists
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, list)| !list.is_empty())
+            .map(|(order, _)| 1usize << order)
+            .unwrap_or(0)
+    }
+
+    fn total_free(&self) -> usize {
+        self.free_lists
+            .iter()
+            .enumerate()
+            .map(|(order, list)| list.len() * (1usize << order))
+            .sum()
+    }
+}
+
+impl Drop for Arena {
+    fn drop(&mut self) {
+        // SAFETY: storage是在`new`里用同一个storage_layout分配的，且只在这里释放一次
+        unsafe { std::alloc::dealloc(self.storage.as_ptr(), self.storage_layout) };
+    }
+}
+
+// `NonNull<u8>` 默认不是 Send/Sync，但arena独占其指向的后备存储，外部只能
+// 通过 `&mut Arena`（受 `MemoryPool` 外层的 `RefCell`/`Mutex` 保护）来改变它，
+// 因此跨线程转移/共享 `Arena` 本身是安全的。
+unsafe impl Send for Arena {}
+unsafe impl Sync for Arena {}
+
+// 内存池整体统计信息
+#[derive(Debug)]
+pub struct PoolStats {
+    pub used_blocks: usize,
+    pub allocated_bytes: usize,
+    pub largest_free_block: usize,
+    pub total_free_bytes: usize,
+}
+
+// 内存池结构体：每个arena都是一棵独立的伙伴树，`blocks`记录每个已分配/已释放
+// 过的块的簿记信息，供 `get_block_info`/`free`/`free_by_id` 按id查找
 #[derive(Debug)]
 pub struct MemoryPool {
-    blocks: Vec<MemoryBlock>,
+    arenas: Vec<Arena>,
+    blocks: HashMap<usize, MemoryBlock>,
     next_id: usize,
     total_allocated: usize,
 }
@@ -145,110 +320,235 @@ pub struct MemoryPool {
 impl MemoryPool {
     pub fn new() -> Rc<RefCell<Self>> {
         Rc::new(RefCell::new(Self {
-            blocks: Vec::new(),
+            arenas: Vec::new(),
+            blocks: HashMap::new(),
             next_id: 1,
             total_allocated: 0,
         }))
     }
-    
-    // 添加新的内存块到池中
+
+    // 添加一个新的伙伴系统arena；size会被向上取整到2的幂
     pub fn add_block(&mut self, size: usize) {
-        self.blocks.push(MemoryBlock {
-            id: self.next_id,
-            size,
-            is_free: true,
-            data: vec![0; size],
-        });
-        self.next_id += 1;
+        self.arenas.push(Arena::new(size));
     }
-    
-    // 分配内存 - 返回拥有所有权的内存句柄
+
+    // 分配内存 - 返回拥有所有权的内存句柄；按大小取整后的阶数在各arena中
+    // 依次尝试，只要有一个arena能分配出对应阶的块就成功
     pub fn allocate(pool: &Rc<RefCell<Self>>, size: usize) -> Option<MemoryHandle> {
         let mut pool_ref = pool.borrow_mut();
-        
-        // 寻找合适的空闲块
-        let mut found_block_id = None;
-        for block in pool_ref.blocks.iter() {
-            if block.is_free && block.size >= size {
-                found_block_id = Some(block.id);
-                break;
-            }
-        }
-        
-        if let Some(block_id) = found_block_id {
-            // 现在修改找到的块
-            for block in pool_ref.blocks.iter_mut() {
-                if block.id == block_id {
-                    block.is_free = false;
-                    pool_ref.total_allocated += size;
-                    
-                    // 创建数据副本 - 所有权转移给句柄
-                    let data = vec![0; size];
-                    
-                    drop(pool_ref); // 释放借用
-                    
-                    return Some(MemoryHandle {
-                        id: block_id,
-                        data,
-                        pool: Rc::clone(pool),
-                    });
-                }
+        let order = order_for_size(size);
+
+        for (arena_index, arena) in pool_ref.arenas.iter_mut().enumerate() {
+            if let Some(offset) = arena.allocate_order(order) {
+                // 指向这个arena真实后备存储里刚分配出来的那一块，不再另外造一份拷贝
+                // SAFETY: offset是刚由这个arena分配出来、尚无其他引用指向的块
+                let ptr = unsafe { arena.block_ptr(offset) };
+
+                let id = pool_ref.next_id;
+                pool_ref.next_id += 1;
+                pool_ref.total_allocated += 1usize << order;
+                pool_ref.blocks.insert(
+                    id,
+                    MemoryBlock {
+                        id,
+                        size,
+                        is_free: false,
+                        arena_index,
+                        offset,
+                        order,
+                    },
+                );
+
+                drop(pool_ref); // 释放借用
+
+                return Some(MemoryHandle {
+                    id,
+                    ptr,
+                    len: size,
+                    pool: Rc::clone(pool),
+                });
             }
         }
-        
+
         None
     }
-    
-    // 通过ID释放内存块
+
+    // 通过ID释放内存块，把底层伙伴块交还给所属arena并尝试与伙伴合并
     fn free_by_id(&mut self, id: usize) {
-        for block in self.blocks.iter_mut() {
-            if block.id == id && !block.is_free {
-                block.is_free = true;
-                self.total_allocated = self.total_allocated.saturating_sub(block.size);
-                println!("   内存块 {} 已释放", id);
-                return;
-            }
+        let Some(block) = self.blocks.get(&id) else {
+            return;
+        };
+        if block.is_free {
+            return;
         }
+        let (arena_index, offset, order) = (block.arena_index, block.offset, block.order);
+
+        self.blocks.get_mut(&id).unwrap().is_free = true;
+        self.total_allocated = self.total_allocated.saturating_sub(1usize << order);
+        self.arenas[arena_index].free_order(offset, order);
+        println!("   内存块 {} 已释放", id);
     }
-    
+
     // 手动释放内存块
     pub fn free(&mut self, id: usize) -> Result<(), &'static str> {
-        for block in self.blocks.iter_mut() {
-            if block.id == id {
-                if !block.is_free {
-                    block.is_free = true;
-                    self.total_allocated = self.total_allocated.saturating_sub(block.size);
-                    return Ok(());
-                } else {
-                    return Err("内存块已经是空闲状态");
-                }
-            }
+        let block = self.blocks.get(&id).ok_or("无效的内存块ID")?;
+        if block.is_free {
+            return Err("内存块已经是空闲状态");
         }
-        Err("无效的内存块ID")
+        let (arena_index, offset, order) = (block.arena_index, block.offset, block.order);
+
+        self.blocks.get_mut(&id).unwrap().is_free = true;
+        self.total_allocated = self.total_allocated.saturating_sub(1usize << order);
+        self.arenas[arena_index].free_order(offset, order);
+        Ok(())
     }
-    
+
     // 获取内存块信息 - 体现借用规则
     pub fn get_block_info(&self, id: usize) -> Option<&MemoryBlock> {
-        self.blocks.iter().find(|block| block.id == id)
+        self.blocks.get(&id)
     }
-    
-    // 统计信息 - 体现借用规则（不可变借用）
-    pub fn stats(&self) -> (usize, usize, usize, usize) {
-        let (mut used, mut free, mut fragmented) = (0, 0, 0);
-        
-        for block in self.blocks.iter() {
-            if block.is_free {
-                free += 1;
-            } else {
-                used += 1;
-                // 简化的碎片检测
-                if block.data.len() > block.size {
-                    fragmented += 1;
+
+    // 统计信息 - 体现借用规则（不可变借用）；largest_free_block/total_free_bytes
+    // 直接来自各arena的伙伴free list，是真实数据而非启发式估算
+    pub fn stats(&self) -> PoolStats {
+        let used_blocks = self.blocks.values().filter(|block| !block.is_free).count();
+        let largest_free_block = self
+            .arenas
+            .iter()
+            .map(Arena::largest_free_block)
+            .max()
+            .unwrap_or(0);
+        let total_free_bytes = self.arenas.iter().map(Arena::total_free).sum();
+
+        PoolStats {
+            used_blocks,
+            allocated_bytes: self.total_allocated,
+            largest_free_block,
+            total_free_bytes,
+        }
+    }
+}
+
+// 让 `MemoryPool` 可以直接给 `Box::new_in`/`Vec::with_capacity_in` 等标准容器当后端。
+// 用 `Rc<RefCell<_>>` 包一层而不是直接为 `MemoryPool` 实现，是因为池本身仍然保持
+// 本文件里一贯的单线程共享所有权模型；需要跨线程使用时应换成 `Arc<Mutex<_>>` 变体。
+//
+// 只在`nightly-allocator` feature下编译：`Allocator`仍是nightly-only trait。
+#[cfg(feature = "nightly-allocator")]
+#[derive(Clone)]
+pub struct PoolRef(Rc<RefCell<MemoryPool>>);
+
+#[cfg(feature = "nightly-allocator")]
+impl PoolRef {
+    pub fn new(pool: Rc<RefCell<MemoryPool>>) -> Self {
+        Self(pool)
+    }
+}
+
+#[cfg(feature = "nightly-allocator")]
+unsafe impl Allocator for PoolRef {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if layout.align() > ARENA_ALIGN {
+            return Err(AllocError);
+        }
+        let order = order_for_size(layout.size().max(layout.align()));
+        let mut pool = self.0.borrow_mut();
+
+        for arena in pool.arenas.iter_mut() {
+            if let Some(offset) = arena.allocate_order(order) {
+                // SAFETY: offset是arena刚分配出来、尚无其他引用指向的块
+                let ptr = unsafe { arena.block_ptr(offset) };
+                return Ok(NonNull::slice_from_raw_parts(ptr, 1usize << order));
+            }
+        }
+        Err(AllocError)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        let order = order_for_size(layout.size().max(layout.align()));
+        let mut pool = self.0.borrow_mut();
+        for arena in pool.arenas.iter_mut() {
+            if arena.owns(ptr) {
+                arena.free_order(arena.offset_of(ptr), order);
+                return;
+            }
+        }
+        // 按照 `Allocator` 的安全契约，传入的指针必须是本分配器之前发出的，
+        // 因此这里不应该发生；保留断言以便在违反契约时尽早发现
+        debug_assert!(false, "deallocate 收到了不属于本池任何arena的指针");
+    }
+}
+
+// 进程级全局分配器适配器：内部持有一个唯一的、线程安全的内存池，
+// 对齐需求超出 `ARENA_ALIGN` 或池已耗尽时，回退到系统分配器。
+// 不持有任何字段，配合 `#[global_allocator]` 使用。
+pub struct PoolGlobalAlloc;
+
+const GLOBAL_POOL_ARENA_SIZE: usize = 1 << 20; // 1MB
+
+static GLOBAL_POOL: Mutex<Option<MemoryPool>> = Mutex::new(None);
+
+impl PoolGlobalAlloc {
+    fn with_pool<R>(f: impl FnOnce(&mut MemoryPool) -> R) -> R {
+        let mut guard = GLOBAL_POOL.lock().unwrap();
+        let pool = guard.get_or_insert_with(|| {
+            let mut pool = MemoryPool {
+                arenas: Vec::new(),
+                blocks: HashMap::new(),
+                next_id: 1,
+                total_allocated: 0,
+            };
+            pool.add_block(GLOBAL_POOL_ARENA_SIZE);
+            pool
+        });
+        f(pool)
+    }
+}
+
+unsafe impl GlobalAlloc for PoolGlobalAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.align() > ARENA_ALIGN {
+            return unsafe { System.alloc(layout) };
+        }
+        let order = order_for_size(layout.size().max(layout.align()));
+        let found = Self::with_pool(|pool| {
+            for arena in pool.arenas.iter_mut() {
+                if let Some(offset) = arena.allocate_order(order) {
+                    // SAFETY: offset刚由同一个arena分配出来，尚无其他引用指向它
+                    return Some(unsafe { arena.block_ptr(offset) });
                 }
             }
+            None
+        });
+
+        match found {
+            Some(ptr) => ptr.as_ptr(),
+            None => unsafe { System.alloc(layout) }, // 池已耗尽，回退到系统分配器
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if layout.align() > ARENA_ALIGN {
+            unsafe { System.dealloc(ptr, layout) };
+            return;
+        }
+        let Some(ptr) = NonNull::new(ptr) else {
+            return;
+        };
+        let order = order_for_size(layout.size().max(layout.align()));
+        let handled = Self::with_pool(|pool| {
+            for arena in pool.arenas.iter_mut() {
+                if arena.owns(ptr) {
+                    arena.free_order(arena.offset_of(ptr), order);
+                    return true;
+                }
+            }
+            false
+        });
+        if !handled {
+            // 不属于池里任何arena的指针（比如超大请求当初走了System），交回系统分配器
+            unsafe { System.dealloc(ptr.as_ptr(), layout) };
         }
-        
-        (used, free, fragmented, self.total_allocated)
     }
 }
 
@@ -338,11 +638,11 @@ fn main() {
     {
         println!("\n5. 内存池统计信息");
         let pool_ref = pool.borrow();
-        let (used, free, fragmented, total_allocated) = pool_ref.stats();
-        println!("   已使用块: {}", used);
-        println!("   空闲块: {}", free);
-        println!("   碎片块: {}", fragmented);
-        println!("   总分配内存: {} 字节", total_allocated);
+        let stats = pool_ref.stats();
+        println!("   已使用块: {}", stats.used_blocks);
+        println!("   总分配内存: {} 字节", stats.allocated_bytes);
+        println!("   最大空闲块: {} 字节", stats.largest_free_block);
+        println!("   空闲内存总量: {} 字节", stats.total_free_bytes);
     }
     
     // 6. 演示作用域和自动释放
@@ -367,6 +667,9 @@ fn main() {
     
     // 调用并发日志系统测试
     test_concurrent_logging();
+
+    // 调用具名Logger管理器测试
+    test_logger_manager();
 }
 
 
@@ -381,7 +684,6 @@ fn main() {
 // 3. 使用模式匹配处理不同的配置值类型
 // 4. 实现自定义错误类型和错误传播
 // 5. 添加配置验证和默认值功能
-use std::collections::HashMap;
 #[derive(Debug, Clone, Copy)]
 enum ConfigFormat {
     Json,
@@ -589,21 +891,29 @@ fn test_config_parser() {
 // 4. 创建后台线程处理日志写入
 // 5. 实现日志轮转和文件管理
 // 6. 添加配置和过滤功能
+// `Trace`比`Debug`更啰嗦，`Fatal`比`Error`更严重；`Off`不是真正会被打印的级别，
+// 只作为`min_level`的哨兵值使用，表示“关闭这个logger的全部输出”。
 #[derive(Debug, Clone)]
 enum LogLevel {
+    Trace,
     Debug,
     Info,
     Warn,
     Error,
+    Fatal,
+    Off,
 }
 
 impl std::fmt::Display for LogLevel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            LogLevel::Trace => write!(f, "TRACE"),
             LogLevel::Debug => write!(f, "DEBUG"),
             LogLevel::Info => write!(f, "INFO"),
             LogLevel::Warn => write!(f, "WARN"),
             LogLevel::Error => write!(f, "ERROR"),
+            LogLevel::Fatal => write!(f, "FATAL"),
+            LogLevel::Off => write!(f, "OFF"),
         }
     }
 }
@@ -612,61 +922,361 @@ struct LogEntry {
     level: LogLevel,
     message: String,
     timestamp: time::SystemTime,
-    dest_path: String,
+    // 以下字段由 `make_log_entry!` 或 `info!`/`error!` 等便捷宏在构造时统一采集，供 `%t`/`%f`/`%l`/`%c` 格式token使用
+    thread_id: std::thread::ThreadId,
+    file: &'static str,
+    line: u32,
+    logger_name: String,
 }
 
 trait LogFormatter {
     fn format(&self, entry: &LogEntry) -> String;
 }
 
-struct DebugLogFormatter;
-struct InfoLogFormatter;
+// 默认的日志输出格式：时间戳 [级别] 消息
+const DEFAULT_LOG_PATTERN: &str = "%d{%Y-%m-%d %H:%M:%S} [%p] %m";
+
+// 解析 `PatternFormatter` 格式串后得到的 token 序列，`%d{...}` 携带自己的子格式
+#[derive(Debug, Clone)]
+enum FormatItem {
+    Literal(String),
+    Timestamp(String),
+    Level,
+    Message,
+    ThreadId,
+    File,
+    Line,
+    LoggerName,
+    Tab,
+    Newline,
+    Percent,
+}
 
-struct WarnLogFormatter;
-struct ErrorLogFormatter;
+// 可配置的日志格式化器：构造时把格式串编译成 `Vec<FormatItem>`，
+// 之后每次 `format()` 只是遍历该向量，避免逐行重新解析模式串。
+// 支持的token：%d{strftime子格式} %p %m %t %f %l %c %T %n %%
+struct PatternFormatter {
+    items: Vec<FormatItem>,
+}
 
-impl LogFormatter for DebugLogFormatter {
-    fn format(&self, entry: &LogEntry) -> String {
-        let timestamp = entry.timestamp.duration_since(time::UNIX_EPOCH)
-            .expect("时间戳错误")
-            .as_secs();
-        format!("{} [{}] {}", timestamp, entry.level.to_string(), entry.message)
+impl PatternFormatter {
+    fn new(pattern: &str) -> Self {
+        Self {
+            items: Self::compile(pattern),
+        }
+    }
+
+    fn compile(pattern: &str) -> Vec<FormatItem> {
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut items = Vec::new();
+        let mut literal = String::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            if chars[i] != '%' || i + 1 >= chars.len() {
+                literal.push(chars[i]);
+                i += 1;
+                continue;
+            }
+
+            let token = chars[i + 1];
+            if token == 'd' {
+                let mut inner = String::new();
+                let mut consumed = 2;
+                if i + 2 < chars.len() && chars[i + 2] == '{' {
+                    let mut j = i + 3;
+                    while j < chars.len() && chars[j] != '}' {
+                        inner.push(chars[j]);
+                        j += 1;
+                    }
+                    consumed = j - i + 1; // 吞掉结尾的 '}'
+                }
+                if !literal.is_empty() {
+                    items.push(FormatItem::Literal(std::mem::take(&mut literal)));
+                }
+                items.push(FormatItem::Timestamp(inner));
+                i += consumed;
+                continue;
+            }
+
+            let item = match token {
+                'p' => Some(FormatItem::Level),
+                'm' => Some(FormatItem::Message),
+                't' => Some(FormatItem::ThreadId),
+                'f' => Some(FormatItem::File),
+                'l' => Some(FormatItem::Line),
+                'c' => Some(FormatItem::LoggerName),
+                'T' => Some(FormatItem::Tab),
+                'n' => Some(FormatItem::Newline),
+                '%' => Some(FormatItem::Percent),
+                _ => None,
+            };
+
+            match item {
+                Some(item) => {
+                    if !literal.is_empty() {
+                        items.push(FormatItem::Literal(std::mem::take(&mut literal)));
+                    }
+                    items.push(item);
+                    i += 2;
+                }
+                None => {
+                    // 未知token，原样保留，避免吞掉用户的字面 '%'
+                    literal.push('%');
+                    literal.push(token);
+                    i += 2;
+                }
+            }
+        }
+
+        if !literal.is_empty() {
+            items.push(FormatItem::Literal(literal));
+        }
+        items
+    }
+
+    // 把 UNIX 秒数按 UTC 换算成日历时间，手工实现以避免引入 chrono 依赖
+    fn render_timestamp(secs: u64, sub_pattern: &str) -> String {
+        if sub_pattern.is_empty() {
+            return secs.to_string();
+        }
+
+        let days = (secs / 86400) as i64;
+        let time_of_day = secs % 86400;
+        let (hour, minute, second) = (time_of_day / 3600, (time_of_day % 3600) / 60, time_of_day % 60);
+
+        // Howard Hinnant 的 civil_from_days 算法
+        let z = days + 719468;
+        let era = if z >= 0 { z } else { z - 146096 } / 146097;
+        let doe = (z - era * 146097) as u64; // [0, 146096]
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365; // [0, 399]
+        let y = yoe as i64 + era * 400;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // [0, 365]
+        let mp = (5 * doy + 2) / 153; // [0, 11]
+        let day = doy - (153 * mp + 2) / 5 + 1; // [1, 31]
+        let month = if mp < 10 { mp + 3 } else { mp - 9 }; // [1, 12]
+        let year = if month <= 2 { y + 1 } else { y };
+
+        let mut out = String::new();
+        let mut chars = sub_pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('Y') => out.push_str(&format!("{:04}", year)),
+                Some('m') => out.push_str(&format!("{:02}", month)),
+                Some('d') => out.push_str(&format!("{:02}", day)),
+                Some('H') => out.push_str(&format!("{:02}", hour)),
+                Some('M') => out.push_str(&format!("{:02}", minute)),
+                Some('S') => out.push_str(&format!("{:02}", second)),
+                Some(other) => {
+                    out.push('%');
+                    out.push(other);
+                }
+                None => out.push('%'),
+            }
+        }
+        out
     }
 }
 
-impl LogFormatter for InfoLogFormatter {
+impl LogFormatter for PatternFormatter {
     fn format(&self, entry: &LogEntry) -> String {
-        let timestamp = entry.timestamp.duration_since(time::UNIX_EPOCH)
-            .expect("时间戳错误")
-            .as_secs();
-        format!("{} [{}] {}", timestamp, entry.level.to_string(), entry.message)
+        let mut out = String::new();
+        for item in &self.items {
+            match item {
+                FormatItem::Literal(s) => out.push_str(s),
+                FormatItem::Timestamp(sub) => {
+                    let secs = entry
+                        .timestamp
+                        .duration_since(time::UNIX_EPOCH)
+                        .expect("时间戳错误")
+                        .as_secs();
+                    out.push_str(&Self::render_timestamp(secs, sub));
+                }
+                FormatItem::Level => out.push_str(&entry.level.to_string()),
+                FormatItem::Message => out.push_str(&entry.message),
+                FormatItem::ThreadId => out.push_str(&format!("{:?}", entry.thread_id)),
+                FormatItem::File => out.push_str(entry.file),
+                FormatItem::Line => out.push_str(&entry.line.to_string()),
+                FormatItem::LoggerName => out.push_str(&entry.logger_name),
+                FormatItem::Tab => out.push('\t'),
+                FormatItem::Newline => out.push('\n'),
+                FormatItem::Percent => out.push('%'),
+            }
+        }
+        out
     }
 }
 
-impl LogFormatter for WarnLogFormatter {
-    fn format(&self, entry: &LogEntry) -> String {
-        let timestamp = entry.timestamp.duration_since(time::UNIX_EPOCH)
-            .expect("时间戳错误")
-            .as_secs();
-        format!("{} [{}] {}", timestamp, entry.level.to_string(), entry.message)
+// 统一构造日志条目，顺带采集线程号与调用位置，避免每个调用点手动重复这些样板代码。
+// 注意：这里的 `file!()`/`line!()` 记录的是宏展开处（即 `Logger::log` 内部）的位置，
+// 并非业务代码的真实调用点；需要精确定位时请使用 `info!`/`error!` 等便捷宏。
+macro_rules! make_log_entry {
+    ($level:expr, $message:expr, $logger_name:expr) => {
+        LogEntry {
+            level: $level,
+            message: $message,
+            timestamp: time::SystemTime::now(),
+            thread_id: std::thread::current().id(),
+            file: file!(),
+            line: line!(),
+            logger_name: $logger_name,
+        }
+    };
+}
+
+// 日志输出目的地：一个 sink 只负责把一行已格式化好的文本写到某处，
+// 让 `process_log_entry` 可以对着一组 sink 扇出，而不必关心具体介质。
+trait LogSink {
+    fn append(&mut self, formatted: &str) -> std::io::Result<()>;
+    fn flush(&mut self) -> std::io::Result<()>;
+}
+
+// 输出到标准输出
+struct StdoutSink;
+
+impl LogSink for StdoutSink {
+    fn append(&mut self, formatted: &str) -> std::io::Result<()> {
+        // `formatted` 在批量flush场景下本身已带换行，这里用print!避免重复换行
+        print!("{}", formatted);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
     }
 }
 
-impl LogFormatter for ErrorLogFormatter {
-    fn format(&self, entry: &LogEntry) -> String {
-        let timestamp = entry.timestamp.duration_since(time::UNIX_EPOCH)
-            .expect("时间戳错误")
-            .as_secs();
-        format!("{} [{}] {}", timestamp, entry.level.to_string(), entry.message)
+// 只追加写入、不做轮转的普通文件
+struct FileSink {
+    path: String,
+}
+
+impl FileSink {
+    fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl LogSink for FileSink {
+    fn append(&mut self, formatted: &str) -> std::io::Result<()> {
+        use std::fs::OpenOptions;
+        use std::io::Write;
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        file.write_all(formatted.as_bytes())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+// 带按体积轮转能力的文件 sink；原先 `Logger::rotate_if_needed`/`rotate_logs`
+// 的逻辑整体搬到这里，成为该 sink 私有的实现细节。
+struct RollingFileSink {
+    path: String,
+    max_file_size: u64,
+    max_files: usize,
+}
+
+impl RollingFileSink {
+    fn new(path: impl Into<String>, max_file_size: u64, max_files: usize) -> Self {
+        Self {
+            path: path.into(),
+            max_file_size,
+            max_files,
+        }
+    }
+
+    fn rotate_if_needed(&self) -> std::io::Result<()> {
+        if let Ok(metadata) = std::fs::metadata(&self.path) {
+            if metadata.len() > self.max_file_size {
+                self.rotate_logs()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn rotate_logs(&self) -> std::io::Result<()> {
+        let path = Path::new(&self.path);
+        let parent = path.parent().unwrap();
+        let stem = path.file_stem().unwrap().to_str().unwrap();
+        let extension = path.extension().unwrap_or_default().to_str().unwrap();
+
+        // 轮转现有文件
+        for i in (1..self.max_files).rev() {
+            let old_file = parent.join(format!("{}.{}.{}", stem, i, extension));
+            let new_file = parent.join(format!("{}.{}.{}", stem, i + 1, extension));
+
+            if old_file.exists() {
+                if i + 1 >= self.max_files {
+                    std::fs::remove_file(&old_file)?; // 删除最老的文件
+                } else {
+                    std::fs::rename(&old_file, &new_file)?;
+                }
+            }
+        }
+
+        // 重命名当前文件
+        let backup_file = parent.join(format!("{}.1.{}", stem, extension));
+        std::fs::rename(&self.path, backup_file)?;
+
+        Ok(())
+    }
+}
+
+impl LogSink for RollingFileSink {
+    fn append(&mut self, formatted: &str) -> std::io::Result<()> {
+        self.rotate_if_needed()?;
+
+        use std::fs::OpenOptions;
+        use std::io::Write;
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        file.write_all(formatted.as_bytes())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
     }
 }
 
+// 声明式的 sink 配置，可 Clone/Debug，随 `LogConfig` 一起存放和下发
+#[derive(Debug, Clone)]
+enum SinkSpec {
+    Stdout,
+    File {
+        path: String,
+    },
+    RollingFile {
+        path: String,
+        max_file_size: u64,
+        max_files: usize,
+    },
+}
 
+// 按配置构造出实际的 sink 实例
+fn sink_from_spec(spec: &SinkSpec) -> Box<dyn LogSink> {
+    match spec {
+        SinkSpec::Stdout => Box::new(StdoutSink),
+        SinkSpec::File { path } => Box::new(FileSink::new(path.clone())),
+        SinkSpec::RollingFile {
+            path,
+            max_file_size,
+            max_files,
+        } => Box::new(RollingFileSink::new(path.clone(), *max_file_size, *max_files)),
+    }
+}
 
-use std::sync::mpsc::{self, Sender, Receiver};
 use std::thread;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
 use std::path::Path;
+use std::time::Duration;
+use std::collections::VecDeque;
 
 // 日志配置结构体
 #[derive(Debug, Clone)]
@@ -675,196 +1285,381 @@ struct LogConfig {
     max_file_size: u64,  // 字节
     max_files: usize,
     log_dir: String,
+    sinks: Vec<SinkSpec>,
+    flush_threshold_bytes: usize, // writer缓冲区达到该大小就立即唤醒worker
+    flush_interval: Duration,     // 即使未达到阈值，也按这个周期兜底flush
+    ring_capacity: usize,         // 保留最近N条渲染后的日志；0表示关闭该功能
 }
 
 impl Default for LogConfig {
     fn default() -> Self {
+        let log_dir = "logs".to_string();
+        let max_file_size = 1024 * 1024; // 1MB
+        let max_files = 5;
+        let sinks = vec![
+            SinkSpec::Stdout,
+            SinkSpec::RollingFile {
+                path: format!("{}/app.log", log_dir),
+                max_file_size,
+                max_files,
+            },
+        ];
+
         Self {
             min_level: LogLevel::Info,
-            max_file_size: 1024 * 1024, // 1MB
-            max_files: 5,
-            log_dir: "logs".to_string(),
+            max_file_size,
+            max_files,
+            log_dir,
+            sinks,
+            flush_threshold_bytes: 4096,
+            flush_interval: Duration::from_millis(200),
+            ring_capacity: 200,
+        }
+    }
+}
+
+// 双缓冲区的可变部分：writer 供生产者追加，reader 是上一轮换出、
+// 尚待worker写盘的稳定内容；shutdown_requested 取代了原先通过
+// mpsc 发送 `LogMessage::Shutdown` 的方式，同样用于通知worker做最后一次flush后退出。
+struct DoubleBufferState {
+    writer: String,
+    reader: String,
+    shutdown_requested: bool,
+}
+
+// 生产者与worker共享的双缓冲区：生产者只在 `push` 里持锁做一次字符串追加，
+// 不接触任何I/O；真正的文件/控制台写入全部发生在worker线程里的批量flush中。
+struct DoubleBuffer {
+    state: Mutex<DoubleBufferState>,
+    cond: Condvar,
+    threshold: usize,
+}
+
+impl DoubleBuffer {
+    fn new(threshold: usize) -> Self {
+        Self {
+            state: Mutex::new(DoubleBufferState {
+                writer: String::new(),
+                reader: String::new(),
+                shutdown_requested: false,
+            }),
+            cond: Condvar::new(),
+            threshold,
         }
     }
+
+    // 生产者路径：加锁追加一行，达到阈值就唤醒worker；不做任何IO
+    fn push(&self, line: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.writer.push_str(line);
+        state.writer.push('\n');
+        if state.writer.len() >= self.threshold {
+            self.cond.notify_one();
+        }
+    }
+
+    fn request_shutdown(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.shutdown_requested = true;
+        self.cond.notify_one();
+    }
+
+    // worker路径：等待阈值、flush定时器或关闭信号之一，然后在持锁状态下交换
+    // writer/reader指针并立即释放锁，返回待写盘的稳定内容以及是否应退出
+    fn wait_and_swap(&self, flush_interval: Duration) -> (String, bool) {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if state.writer.len() >= self.threshold || state.shutdown_requested {
+                break;
+            }
+            let (guard, timeout) = self.cond.wait_timeout(state, flush_interval).unwrap();
+            state = guard;
+            if timeout.timed_out() {
+                break; // 定时器兜底，避免少量日志长期滞留在writer里
+            }
+        }
+        let state = &mut *state;
+        std::mem::swap(&mut state.writer, &mut state.reader);
+        let data = std::mem::take(&mut state.reader);
+        (data, state.shutdown_requested)
+    }
+}
+
+// 保留最近N条渲染后的日志文本，供出错时回看上下文用。用 `VecDeque` 实现
+// O(1)的尾部追加与头部丢弃；capacity为0时彻底关闭该功能（不做任何记录）。
+struct RingBuffer {
+    capacity: usize,
+    entries: Mutex<VecDeque<String>>,
+}
+
+impl RingBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    fn push(&self, line: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(line);
+    }
+
+    // 取最近n条（不清空），按时间从早到晚排列
+    fn recent(&self, n: usize) -> Vec<String> {
+        let entries = self.entries.lock().unwrap();
+        let skip = entries.len().saturating_sub(n);
+        entries.iter().skip(skip).cloned().collect()
+    }
+
+    // 取出全部缓存内容并清空
+    fn drain_recent(&self) -> Vec<String> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.drain(..).collect()
+    }
 }
 
 // 日志系统主结构体
 struct Logger {
-    sender: Sender<LogMessage>,
+    name: String,
+    buffers: Arc<DoubleBuffer>,
+    formatter: Arc<PatternFormatter>,
+    ring: Arc<RingBuffer>,
     config: Arc<Mutex<LogConfig>>,
     _worker_handle: thread::JoinHandle<()>,
 }
 
-// 日志消息枚举，支持关闭信号
-enum LogMessage {
-    Entry(LogEntry),
-    Shutdown,
-}
-
 impl Logger {
-    fn new(config: LogConfig) -> std::io::Result<Self> {
-        let (sender, receiver) = mpsc::channel();
+    fn new(name: impl Into<String>, config: LogConfig) -> std::io::Result<Self> {
+        let flush_threshold_bytes = config.flush_threshold_bytes;
+        let flush_interval = config.flush_interval;
+        let ring_capacity = config.ring_capacity;
         let config_arc = Arc::new(Mutex::new(config));
-        let worker_config = Arc::clone(&config_arc);
-        
+
         // 创建日志目录
-        let log_dir = {
-            let config_guard = worker_config.lock().unwrap();
-            config_guard.log_dir.clone()
-        };
+        let log_dir = config_arc.lock().unwrap().log_dir.clone();
         std::fs::create_dir_all(&log_dir)?;
-        
+
+        let buffers = Arc::new(DoubleBuffer::new(flush_threshold_bytes));
+        let formatter = Arc::new(PatternFormatter::new(DEFAULT_LOG_PATTERN));
+        let ring = Arc::new(RingBuffer::new(ring_capacity));
+
         // 启动后台工作线程
+        let worker_buffers = Arc::clone(&buffers);
+        let worker_config = Arc::clone(&config_arc);
         let worker_handle = thread::spawn(move || {
-            Self::log_worker(receiver, worker_config);
+            Self::log_worker(worker_buffers, worker_config, flush_interval);
         });
-        
+
         Ok(Logger {
-            sender,
+            name: name.into(),
+            buffers,
+            formatter,
+            ring,
             config: config_arc,
             _worker_handle: worker_handle,
         })
     }
-    
-    // 异步写入日志
+
+    // 写入日志：生产者只做格式化和一次加锁追加，真正的IO留给worker批量处理
     fn log(&self, level: LogLevel, message: String) -> Result<(), Box<dyn std::error::Error>> {
-        // 检查日志级别过滤
+        let entry = make_log_entry!(level, message, self.name.clone());
+        self.log_entry(entry)
+    }
+
+    // 供 `info!`/`error!` 等便捷宏使用：调用处已经带着自己的 `file!()`/`line!()`
+    // 构造好了完整的entry，这里只负责过滤、格式化和投递，不再重复采集位置信息
+    fn log_entry(&self, entry: LogEntry) -> Result<(), Box<dyn std::error::Error>> {
         let config = self.config.lock().unwrap();
-        if !Self::should_log(&level, &config.min_level) {
+        if !Self::should_log(&entry.level, &config.min_level) {
             return Ok(());
         }
-        
-        let log_dir = config.log_dir.clone();
         drop(config); // 释放锁
-        
-        let entry = LogEntry {
-            level,
-            message,
-            timestamp: time::SystemTime::now(),
-            dest_path: format!("{}/app.log", log_dir),
-        };
-        
-        self.sender.send(LogMessage::Entry(entry))?;
+
+        let formatted = self.formatter.format(&entry);
+        self.ring.push(formatted.clone());
+        self.buffers.push(&formatted);
         Ok(())
     }
-    
+
+    // 返回最近的n条渲染后的日志（不清空环形缓冲区），供出错时回看上下文
+    pub fn recent(&self, n: usize) -> Vec<String> {
+        self.ring.recent(n)
+    }
+
+    // 取出环形缓冲区中全部内容并清空
+    pub fn drain_recent(&self) -> Vec<String> {
+        self.ring.drain_recent()
+    }
+
+    // 日志级别的优先级，数值越大越严重；`Off`高于`Fatal`，专门用作过滤哨兵
+    fn level_priority(level: &LogLevel) -> u8 {
+        match level {
+            LogLevel::Trace => 0,
+            LogLevel::Debug => 1,
+            LogLevel::Info => 2,
+            LogLevel::Warn => 3,
+            LogLevel::Error => 4,
+            LogLevel::Fatal => 5,
+            LogLevel::Off => 6,
+        }
+    }
+
     // 检查是否应该记录日志
     fn should_log(level: &LogLevel, min_level: &LogLevel) -> bool {
-        let level_priority = match level {
-            LogLevel::Debug => 0,
-            LogLevel::Info => 1,
-            LogLevel::Warn => 2,
-            LogLevel::Error => 3,
-        };
-        
-        let min_priority = match min_level {
-            LogLevel::Debug => 0,
-            LogLevel::Info => 1,
-            LogLevel::Warn => 2,
-            LogLevel::Error => 3,
-        };
-        
-        level_priority >= min_priority
+        Self::level_priority(level) >= Self::level_priority(min_level)
     }
-    
-    // 后台工作线程
-    fn log_worker(receiver: Receiver<LogMessage>, config: Arc<Mutex<LogConfig>>) {
-        while let Ok(message) = receiver.recv() {
-            match message {
-                LogMessage::Entry(entry) => {
-                    if let Err(e) = Self::process_log_entry(entry, &config) {
-                        eprintln!("日志处理错误: {}", e);
-                    }
-                }
-                LogMessage::Shutdown => {
-                    println!("日志系统正在关闭...");
-                    break;
+
+    // 后台工作线程：等待换出信号，批量落盘，直到收到关闭请求
+    fn log_worker(buffers: Arc<DoubleBuffer>, config: Arc<Mutex<LogConfig>>, flush_interval: Duration) {
+        loop {
+            let (data, shutdown_requested) = buffers.wait_and_swap(flush_interval);
+            if !data.is_empty() {
+                if let Err(e) = Self::flush_to_sinks(&data, &config) {
+                    eprintln!("日志处理错误: {}", e);
                 }
             }
-        }
-    }
-    
-    // 处理单个日志条目
-    fn process_log_entry(entry: LogEntry, config: &Arc<Mutex<LogConfig>>) -> std::io::Result<()> {
-        let formatter: Box<dyn LogFormatter> = match entry.level {
-            LogLevel::Debug => Box::new(DebugLogFormatter),
-            LogLevel::Info => Box::new(InfoLogFormatter),
-            LogLevel::Warn => Box::new(WarnLogFormatter),
-            LogLevel::Error => Box::new(ErrorLogFormatter),
-        };
-        
-        let formatted = formatter.format(&entry);
-        
-        // 控制台输出
-        println!("{}", formatted);
-        
-        // 检查文件大小并轮转
-        let config_guard = config.lock().unwrap();
-        Self::rotate_if_needed(&entry.dest_path, &config_guard)?;
-        drop(config_guard);
-        
-        // 写入文件
-        use std::fs::OpenOptions;
-        use std::io::Write;
-        
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&entry.dest_path)?;
-        
-        writeln!(file, "{}", formatted)?;
-        Ok(())
-    }
-    
-    // 日志轮转
-    fn rotate_if_needed(log_path: &str, config: &LogConfig) -> std::io::Result<()> {
-        if let Ok(metadata) = std::fs::metadata(log_path) {
-            if metadata.len() > config.max_file_size {
-                Self::rotate_logs(log_path, config)?;
+            if shutdown_requested {
+                println!("日志系统正在关闭...");
+                break;
             }
         }
-        Ok(())
     }
-    
-    // 执行日志轮转
-    fn rotate_logs(log_path: &str, config: &LogConfig) -> std::io::Result<()> {
-        let path = Path::new(log_path);
-        let parent = path.parent().unwrap();
-        let stem = path.file_stem().unwrap().to_str().unwrap();
-        let extension = path.extension().unwrap_or_default().to_str().unwrap();
-        
-        // 轮转现有文件
-        for i in (1..config.max_files).rev() {
-            let old_file = parent.join(format!("{}.{}.{}", stem, i, extension));
-            let new_file = parent.join(format!("{}.{}.{}", stem, i + 1, extension));
-            
-            if old_file.exists() {
-                if i + 1 >= config.max_files {
-                    std::fs::remove_file(&old_file)?; // 删除最老的文件
-                } else {
-                    std::fs::rename(&old_file, &new_file)?;
-                }
-            }
+
+    // 把一批已格式化好的日志文本一次性写给配置的每一个 sink
+    fn flush_to_sinks(batch: &str, config: &Arc<Mutex<LogConfig>>) -> std::io::Result<()> {
+        let sinks = config.lock().unwrap().sinks.clone();
+        for spec in &sinks {
+            let mut sink = sink_from_spec(spec);
+            sink.append(batch)?;
+            sink.flush()?;
         }
-        
-        // 重命名当前文件
-        let backup_file = parent.join(format!("{}.1.{}", stem, extension));
-        std::fs::rename(log_path, backup_file)?;
-        
         Ok(())
     }
-    
+
     // 更新配置
     fn update_config(&self, new_config: LogConfig) {
         let mut config = self.config.lock().unwrap();
         *config = new_config;
     }
-    
-    // 优雅关闭
+
+    // 优雅关闭：通知worker做最后一次flush后退出
     fn shutdown(self) -> Result<(), Box<dyn std::error::Error>> {
-        self.sender.send(LogMessage::Shutdown)?;
-        // 注意：这里我们不能等待线程结束，因为会消费self
+        self.buffers.request_shutdown();
+        // 注意：这里我们不等待线程结束，因为会消费self
         // 在实际应用中，可能需要不同的设计来处理这个问题
         Ok(())
     }
+}
+
+// 默认（未命名调用）使用的具名logger的名字
+const DEFAULT_LOGGER_NAME: &str = "default";
+
+// 具名logger的全局注册表：不同子系统可以各自用 `LoggerManager::get("net")` 拿到
+// 一个共享的、独立配置（级别/sink）的logger，而不必把logger实例层层传递下去。
+struct LoggerManager {
+    loggers: Mutex<HashMap<String, Arc<Logger>>>,
+}
+
+impl LoggerManager {
+    fn global() -> &'static LoggerManager {
+        static INSTANCE: OnceLock<LoggerManager> = OnceLock::new();
+        INSTANCE.get_or_init(|| LoggerManager {
+            loggers: Mutex::new(HashMap::new()),
+        })
+    }
+
+    // 获取具名logger，首次访问时用默认配置惰性创建；已存在则直接复用
+    fn get(name: &str) -> Arc<Logger> {
+        Self::get_or_create(name, LogConfig::default)
+    }
+
+    // 获取具名logger，若尚未创建则用`make_config`构造的配置首次创建；
+    // 配置仅在创建时生效一次，之后同名调用一律复用已有实例
+    fn get_or_create(name: &str, make_config: impl FnOnce() -> LogConfig) -> Arc<Logger> {
+        let manager = Self::global();
+        let mut loggers = manager.loggers.lock().unwrap();
+        if let Some(logger) = loggers.get(name) {
+            return Arc::clone(logger);
+        }
+        let logger = Arc::new(Logger::new(name, make_config()).expect("创建具名日志器失败"));
+        loggers.insert(name.to_string(), Arc::clone(&logger));
+        logger
+    }
+
+    // 未指定名字时使用的默认logger
+    fn default_logger() -> Arc<Logger> {
+        Self::get(DEFAULT_LOGGER_NAME)
+    }
+}
+
+// 便捷日志宏：捕获调用处真实的 `file!()`/`line!()`，并路由到默认的具名logger，
+// 取代逐处手写 `logger.log(LogLevel::Info, format!(...))` 的啰嗦写法
+macro_rules! log_with_level {
+    ($level:expr, $($arg:tt)*) => {{
+        let logger = LoggerManager::default_logger();
+        let entry = LogEntry {
+            level: $level,
+            message: format!($($arg)*),
+            timestamp: time::SystemTime::now(),
+            thread_id: std::thread::current().id(),
+            file: file!(),
+            line: line!(),
+            logger_name: logger.name.clone(),
+        };
+        let _ = logger.log_entry(entry);
+    }};
+}
+
+macro_rules! trace {
+    ($($arg:tt)*) => { log_with_level!(LogLevel::Trace, $($arg)*) };
+}
+macro_rules! debug {
+    ($($arg:tt)*) => { log_with_level!(LogLevel::Debug, $($arg)*) };
+}
+macro_rules! info {
+    ($($arg:tt)*) => { log_with_level!(LogLevel::Info, $($arg)*) };
+}
+macro_rules! warn {
+    ($($arg:tt)*) => { log_with_level!(LogLevel::Warn, $($arg)*) };
+}
+macro_rules! error {
+    ($($arg:tt)*) => { log_with_level!(LogLevel::Error, $($arg)*) };
+}
+macro_rules! fatal {
+    ($($arg:tt)*) => { log_with_level!(LogLevel::Fatal, $($arg)*) };
+}
+
+// 具名logger 与 便捷宏 的演示
+fn test_logger_manager() {
+    println!("\n=== 具名Logger管理器演示 ===");
+
+    // 未指定名字时，这些宏都落在同一个"default" logger上
+    info!("应用程序启动，pid相关信息省略");
+    warn!("这是通过便捷宏记录的警告，参数: {}", 42);
+
+    // 不同子系统各自拿自己的具名logger，配置互不影响
+    let net_logger = LoggerManager::get("net");
+    net_logger.log(LogLevel::Info, "网络模块已连接".to_string()).unwrap();
+
+    let mut db_config = LogConfig::default();
+    db_config.min_level = LogLevel::Off;
+    let db_logger = LoggerManager::get_or_create("db", || db_config);
+    // db_logger被配置为Off，下面这条不会被记录
+    db_logger.log(LogLevel::Fatal, "这条不应该出现".to_string()).unwrap();
+
+    // 再次按名字获取，拿到的是同一个已注册实例
+    let net_logger_again = LoggerManager::get("net");
+    net_logger_again
+        .log(LogLevel::Debug, "复用同一个具名logger".to_string())
+        .unwrap();
+
+    thread::sleep(std::time::Duration::from_millis(50));
+    println!("=== 具名Logger管理器演示完成 ===");
 }
\ No newline at end of file
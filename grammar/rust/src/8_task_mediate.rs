@@ -26,16 +26,23 @@
 // cargo run --bin 8_task_mediate_simple
 // cargo test --bin 8_task_mediate_simple
 
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
 use std::time::Duration;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use tokio::sync::{Mutex, Semaphore};
-use reqwest::{Client as ReqwestClient, Method, Response, Url};
+use reqwest::{Client as ReqwestClient, Method, Url};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use async_trait::async_trait;
-use futures::future::BoxFuture;
 use log::{info, warn, error};
+use rand::Rng;
+use futures::stream::{try_unfold, BoxStream, Stream};
+use futures::StreamExt;
+use bytes::Bytes;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 
 // 错误处理
 #[derive(Error, Debug)]
@@ -56,6 +63,108 @@ pub enum HttpClientError {
 
 pub type Result<T> = std::result::Result<T, HttpClientError>;
 
+/// A `multipart/form-data` body builder for [`HttpRequest::multipart`].
+/// Unlike `reqwest::multipart::Form`, this is `Clone`/`Debug` because it
+/// just holds owned text/bytes — `HttpRequest` needs to stay `Clone` so
+/// `RetryService` can replay it across attempts.
+#[derive(Debug, Clone, Default)]
+pub struct MultipartForm {
+    boundary: String,
+    parts: Vec<MultipartPart>,
+}
+
+#[derive(Debug, Clone)]
+enum MultipartPartBody {
+    Text(String),
+    File {
+        filename: String,
+        content_type: String,
+        bytes: Vec<u8>,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct MultipartPart {
+    name: String,
+    body: MultipartPartBody,
+}
+
+impl MultipartForm {
+    pub fn new() -> Self {
+        Self {
+            // 128 bits of randomness rather than 64: a boundary that collides
+            // with literal part content would corrupt the body, and the
+            // extra bits make that chance negligible even for large uploads.
+            boundary: format!("----boundary-{:032x}", rand::thread_rng().gen::<u128>()),
+            parts: Vec::new(),
+        }
+    }
+
+    /// Adds a plain text field.
+    pub fn text(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.parts.push(MultipartPart {
+            name: name.into(),
+            body: MultipartPartBody::Text(value.into()),
+        });
+        self
+    }
+
+    /// Adds a file part with an explicit filename and content type.
+    pub fn file(
+        mut self,
+        name: impl Into<String>,
+        filename: impl Into<String>,
+        content_type: impl Into<String>,
+        bytes: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.parts.push(MultipartPart {
+            name: name.into(),
+            body: MultipartPartBody::File {
+                filename: filename.into(),
+                content_type: content_type.into(),
+                bytes: bytes.into(),
+            },
+        });
+        self
+    }
+
+    fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+
+        for part in &self.parts {
+            out.extend_from_slice(format!("--{}\r\n", self.boundary).as_bytes());
+
+            match &part.body {
+                MultipartPartBody::Text(value) => {
+                    out.extend_from_slice(
+                        format!("Content-Disposition: form-data; name=\"{}\"\r\n\r\n", part.name).as_bytes(),
+                    );
+                    out.extend_from_slice(value.as_bytes());
+                }
+                MultipartPartBody::File { filename, content_type, bytes } => {
+                    out.extend_from_slice(
+                        format!(
+                            "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+                            part.name, filename, content_type
+                        )
+                        .as_bytes(),
+                    );
+                    out.extend_from_slice(bytes);
+                }
+            }
+
+            out.extend_from_slice(b"\r\n");
+        }
+
+        out.extend_from_slice(format!("--{}--\r\n", self.boundary).as_bytes());
+        out
+    }
+}
+
 // HTTP请求结构
 #[derive(Debug, Clone)]
 pub struct HttpRequest {
@@ -64,19 +173,23 @@ pub struct HttpRequest {
     pub headers: HashMap<String, String>,
     pub body: Option<Vec<u8>>,
     pub timeout: Option<Duration>,
+    /// Set by `HttpClient::stream` so `PoolService::call` hands back the body
+    /// as a `Stream` instead of buffering it into `HttpResponse::body`.
+    pub(crate) want_stream: bool,
 }
 
 impl HttpRequest {
     pub fn new(method: Method, url: &str) -> Result<Self> {
         let parsed_url = Url::parse(url)
             .map_err(|e| HttpClientError::UrlParseError(e.to_string()))?;
-        
+
         Ok(Self {
             method,
             url: parsed_url,
             headers: HashMap::new(),
             body: None,
             timeout: None,
+            want_stream: false,
         })
     }
 
@@ -85,24 +198,72 @@ impl HttpRequest {
         self
     }
 
+    /// Serializes `data` as the JSON body and sets `Content-Type:
+    /// application/json`. Don't also call `.header("Content-Type", ...)` —
+    /// `.json`/`.form`/`.multipart` each set it for you.
     pub fn json<T: Serialize>(mut self, data: &T) -> Result<Self> {
         self.body = Some(serde_json::to_vec(data)?);
         self.headers.insert("Content-Type".to_string(), "application/json".to_string());
         Ok(self)
     }
 
+    /// Serializes `data` as `application/x-www-form-urlencoded` (e.g.
+    /// `foo=bar&baz=quux`) and sets the matching `Content-Type`. Accepts
+    /// tuple slices, `HashMap`s, or any `Serialize` type that encodes to a
+    /// flat set of key-value pairs.
+    pub fn form<T: Serialize>(mut self, data: &T) -> Result<Self> {
+        let encoded = serde_urlencoded::to_string(data)
+            .map_err(|e| HttpClientError::MiddlewareError(format!("form encoding failed: {e}")))?;
+        self.body = Some(encoded.into_bytes());
+        self.headers.insert("Content-Type".to_string(), "application/x-www-form-urlencoded".to_string());
+        Ok(self)
+    }
+
+    /// Encodes `form` as a `multipart/form-data` body (with a generated
+    /// boundary) and sets the matching `Content-Type`. The body is
+    /// materialized into bytes up front so `HttpRequest` stays `Clone`,
+    /// which `RetryService` relies on to replay a request across attempts.
+    pub fn multipart(mut self, form: MultipartForm) -> Self {
+        self.headers.insert("Content-Type".to_string(), form.content_type());
+        self.body = Some(form.encode());
+        self
+    }
+
     pub fn timeout(mut self, timeout: Duration) -> Self {
         self.timeout = Some(timeout);
         self
     }
+
+    pub(crate) fn streaming(mut self) -> Self {
+        self.want_stream = true;
+        self
+    }
 }
 
 // HTTP响应结构
-#[derive(Debug, Clone)]
+//
+// `body` holds the fully-buffered bytes for the normal `request`/`get`/`post`
+// path. `body_stream` is only populated when the originating `HttpRequest`
+// had `want_stream` set (i.e. it came from `HttpClient::stream`), in which
+// case `body` is left empty and the caller should consume the stream
+// instead. `Stream`s aren't `Clone`/`Debug`, so this type no longer derives
+// either; `Debug` is implemented by hand, omitting the stream.
 pub struct HttpResponse {
     pub status: u16,
     pub headers: HashMap<String, String>,
     pub body: Vec<u8>,
+    pub body_stream: Option<BoxStream<'static, Result<Bytes>>>,
+}
+
+impl std::fmt::Debug for HttpResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpResponse")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .field("body", &self.body)
+            .field("body_stream", &self.body_stream.is_some())
+            .finish()
+    }
 }
 
 impl HttpResponse {
@@ -119,235 +280,1019 @@ impl HttpResponse {
     }
 }
 
-// 中间件trait
-#[async_trait]
-pub trait Middleware: Send + Sync {
-    async fn handle(&self, request: &mut HttpRequest, next: Next<'_>) -> Result<HttpResponse>;
+/// Returned by [`HttpClient::stream`]: status and headers are already
+/// available, and the body is consumed incrementally instead of being
+/// buffered in full.
+pub struct HttpResponseStream {
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    pub body: BoxStream<'static, Result<Bytes>>,
 }
-#[derive(Clone)]
-pub struct Next<'a> {
-    middlewares: &'a [Arc<dyn Middleware>],
-    index: usize,
-    client: &'a HttpClient,
+
+impl HttpResponseStream {
+    pub fn is_success(&self) -> bool {
+        self.status >= 200 && self.status < 300
+    }
+
+    /// Pumps the body stream to `writer` chunk by chunk, so memory use stays
+    /// bounded by the chunk size regardless of the response's total length.
+    /// Returns the total number of bytes written.
+    pub async fn copy_to<W: AsyncWrite + Unpin>(mut self, mut writer: W) -> Result<u64> {
+        let mut total = 0u64;
+
+        while let Some(chunk) = self.body.next().await {
+            let chunk = chunk?;
+            writer.write_all(&chunk).await.map_err(|e| HttpClientError::MiddlewareError(e.to_string()))?;
+            total += chunk.len() as u64;
+        }
+
+        writer.flush().await.map_err(|e| HttpClientError::MiddlewareError(e.to_string()))?;
+        Ok(total)
+    }
 }
 
-impl<'a> Next<'a> {
-    pub async fn run(mut self, request: &mut HttpRequest) -> Result<HttpResponse> {
-        if self.index < self.middlewares.len() {
-            let middleware = self.middlewares[self.index].clone();
-            self.index += 1;
-            middleware.handle(request, self).await
-        } else {
-            self.client.execute_request(request).await
+// ============================================================================
+// Pagination
+// ============================================================================
+
+/// State threaded through `try_unfold` by `HttpClient::paginate`: items
+/// already fetched but not yet yielded, and the request for the next page
+/// (if any `rel="next"` link remains).
+struct PaginationState<T> {
+    items: VecDeque<T>,
+    next_request: Option<HttpRequest>,
+}
+
+/// Parses the `Link` header for an RFC 8288 entry with `rel="next"` and
+/// turns it into the next `HttpRequest`, preserving the original request's
+/// headers and timeout (everything except the URL).
+fn next_page_request(response: &HttpResponse, current: &HttpRequest) -> Result<Option<HttpRequest>> {
+    let Some(link_header) = response.headers.get("Link").or_else(|| response.headers.get("link")) else {
+        return Ok(None);
+    };
+
+    for entry in link_header.split(',') {
+        let mut parts = entry.split(';');
+        let Some(url_part) = parts.next() else { continue };
+        let is_next = parts.any(|param| {
+            let param = param.trim();
+            param == "rel=\"next\"" || param == "rel=next"
+        });
+
+        if !is_next {
+            continue;
         }
+
+        let url = url_part.trim().trim_start_matches('<').trim_end_matches('>');
+        let mut next = current.clone();
+        next.url = Url::parse(url).map_err(|e| HttpClientError::UrlParseError(e.to_string()))?;
+        return Ok(Some(next));
     }
+
+    Ok(None)
 }
 
-// 日志中间件
-pub struct LoggingMiddleware;
+/// Pulls the array of page items out of a response body: either the body
+/// itself (when `field` is `None`) or a named top-level field of it.
+fn extract_page<T: for<'de> Deserialize<'de>>(body: &[u8], field: Option<&str>) -> Result<Vec<T>> {
+    let value: serde_json::Value = serde_json::from_slice(body)?;
+
+    let array = match field {
+        Some(field) => value
+            .get(field)
+            .cloned()
+            .ok_or_else(|| HttpClientError::MiddlewareError(format!("missing field `{field}` in paginated response")))?,
+        None => value,
+    };
 
-#[async_trait]
-impl Middleware for LoggingMiddleware {
-    async fn handle(&self, request: &mut HttpRequest, next: Next<'_>) -> Result<HttpResponse> {
-        info!("Sending {} request to {}", request.method, request.url);
+    Ok(serde_json::from_value(array)?)
+}
+
+// ============================================================================
+// Service/Layer stack
+// ============================================================================
+//
+// The old design walked a `&[Arc<dyn Middleware>]` by index inside `Next::run`,
+// which only ever let a middleware run an `async fn handle(request, next)` call
+// chain: there was no way to ask "is the next stage even ready to accept work"
+// without already committing to the call. We replace it with a Tower-style
+// pair of traits:
+//
+// - `Service::poll_ready` reports readiness (e.g. the connection pool's
+//   semaphore has a free permit) *before* `call` is invoked, so a saturated
+//   downstream can return `Poll::Pending` instead of awaiting inside `call`.
+//   Callers must drive `poll_ready` to `Ready(Ok(()))` before calling `call`.
+// - `Layer::layer` wraps an inner service in another, so middlewares compose
+//   by wrapping rather than by walking an index.
+//
+// `HttpClient::request` builds a fresh service stack per call from cheaply
+// cloneable pieces (the `reqwest::Client` and the pool's `Arc<Semaphore>`),
+// so concurrent requests share no per-stack state; they contend only on the
+// shared pieces: the semaphore, plus the rate limiter or cache if configured.
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A unit of request/response handling that can signal backpressure before
+/// it's asked to do work.
+pub trait Service {
+    type Response;
+    type Error;
+
+    /// Returns `Ready(Ok(()))` once this service (and everything it wraps) can
+    /// accept a `call` without blocking. A saturated downstream (e.g. the
+    /// connection pool) returns `Pending` here instead of awaiting in `call`.
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>>;
+
+    /// Handle one request. Callers must have just observed `poll_ready`
+    /// return `Ready(Ok(()))`.
+    fn call(&mut self, req: HttpRequest) -> BoxFuture<'_, std::result::Result<Self::Response, Self::Error>>;
+}
+
+/// Boxed, type-erased `Service` so the builder can assemble a stack of
+/// differently-typed middlewares into one value.
+pub type BoxedService = Box<dyn Service<Response = HttpResponse, Error = HttpClientError> + Send>;
+
+/// Wraps an inner service in another, producing a new (possibly differently
+/// typed) service. Composition happens by repeated wrapping rather than by
+/// walking an index into a list.
+pub trait Layer<S> {
+    type Service;
+    fn layer(&self, inner: S) -> Self::Service;
+}
+
+/// Drives `svc.poll_ready` to completion, then issues `call`. This is the
+/// invariant every caller of a `Service` must uphold.
+async fn ready_and_call(svc: &mut BoxedService, req: HttpRequest) -> Result<HttpResponse> {
+    std::future::poll_fn(|cx| svc.poll_ready(cx)).await?;
+    svc.call(req).await
+}
+
+// ============================================================================
+// Innermost service: the connection pool
+// ============================================================================
+
+/// Connection pool, now the innermost `Service` in the stack. The semaphore
+/// permit is reserved in `poll_ready` (not awaited inside `call`), which is
+/// what gives the whole stack real backpressure: a caller that polls a
+/// saturated pool gets `Pending` and can shed load instead of piling up
+/// pending `.await`s.
+pub struct PoolService {
+    client: ReqwestClient,
+    semaphore: Arc<Semaphore>,
+    acquiring: Option<BoxFuture<'static, std::result::Result<tokio::sync::OwnedSemaphorePermit, tokio::sync::AcquireError>>>,
+    permit: Option<tokio::sync::OwnedSemaphorePermit>,
+}
+
+impl PoolService {
+    pub fn new(client: ReqwestClient, semaphore: Arc<Semaphore>) -> Self {
+        Self {
+            client,
+            semaphore,
+            acquiring: None,
+            permit: None,
+        }
+    }
+}
+
+impl Service for PoolService {
+    type Response = HttpResponse;
+    type Error = HttpClientError;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        if self.permit.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+
+        let fut = self.acquiring.get_or_insert_with(|| {
+            let semaphore = self.semaphore.clone();
+            Box::pin(async move { semaphore.acquire_owned().await })
+        });
+
+        match fut.as_mut().poll(cx) {
+            Poll::Ready(Ok(permit)) => {
+                self.permit = Some(permit);
+                self.acquiring = None;
+                Poll::Ready(Ok(()))
+            }
+            Poll::Ready(Err(_)) => {
+                self.acquiring = None;
+                Poll::Ready(Err(HttpClientError::PoolExhausted))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn call(&mut self, req: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        let permit = self.permit.take().expect("poll_ready must return Ready before call");
+        let client = self.client.clone();
+
+        Box::pin(async move {
+            let _permit = permit; // held for the duration of the request, then released
+
+            let mut req_builder = client.request(req.method.clone(), req.url.clone());
+
+            for (key, value) in &req.headers {
+                req_builder = req_builder.header(key, value);
+            }
+
+            if let Some(body) = &req.body {
+                req_builder = req_builder.body(body.clone());
+            }
+
+            if let Some(timeout) = req.timeout {
+                req_builder = req_builder.timeout(timeout);
+            }
+
+            let response = req_builder.send().await?;
+
+            let status = response.status().as_u16();
+            let headers: HashMap<String, String> = response.headers()
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
+                .collect();
+
+            // Headers/status are available as soon as `send` resolves, so
+            // both paths below surface them before the body is read — the
+            // streaming path just defers reading the body itself.
+            if req.want_stream {
+                let stream = response
+                    .bytes_stream()
+                    .map(|chunk| chunk.map_err(HttpClientError::from))
+                    .boxed();
+
+                Ok(HttpResponse {
+                    status,
+                    headers,
+                    body: Vec::new(),
+                    body_stream: Some(stream),
+                })
+            } else {
+                let body = response.bytes().await?.to_vec();
+
+                Ok(HttpResponse {
+                    status,
+                    headers,
+                    body,
+                    body_stream: None,
+                })
+            }
+        })
+    }
+}
+
+// ============================================================================
+// Logging layer
+// ============================================================================
+
+pub struct LoggingService {
+    inner: BoxedService,
+}
+
+impl Service for LoggingService {
+    type Response = HttpResponse;
+    type Error = HttpClientError;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        info!("Sending {} request to {}", req.method, req.url);
         let start = std::time::Instant::now();
-        
-        let response = next.run(request).await;
-        
-        let duration = start.elapsed();
-        match &response {
-            Ok(resp) => info!("Request completed in {:?} with status {}", duration, resp.status),
-            Err(e) => error!("Request failed in {:?}: {}", duration, e),
+        let fut = self.inner.call(req);
+
+        Box::pin(async move {
+            let response = fut.await;
+            let duration = start.elapsed();
+            match &response {
+                Ok(resp) => info!("Request completed in {:?} with status {}", duration, resp.status),
+                Err(e) => error!("Request failed in {:?}: {}", duration, e),
+            }
+            response
+        })
+    }
+}
+
+pub struct LoggingLayer;
+
+impl Layer<BoxedService> for LoggingLayer {
+    type Service = BoxedService;
+
+    fn layer(&self, inner: BoxedService) -> BoxedService {
+        Box::new(LoggingService { inner })
+    }
+}
+
+// ============================================================================
+// Retry layer
+// ============================================================================
+
+/// Decides whether a given attempt's outcome is worth retrying at all.
+/// Separated from `RetryPolicy` so callers can plug in their own notion of
+/// "retryable" (e.g. treating a custom 5xx body as transient) without
+/// reimplementing the backoff math.
+pub trait RetryableClassifier: Send + Sync {
+    fn should_retry(&self, outcome: &Result<HttpResponse>) -> bool;
+}
+
+/// The default classifier: connection errors, timeouts, and the status
+/// codes that are conventionally safe to retry (429 plus the 5xx codes that
+/// usually mean "overloaded, try again").
+pub struct DefaultRetryableClassifier;
+
+impl RetryableClassifier for DefaultRetryableClassifier {
+    fn should_retry(&self, outcome: &Result<HttpResponse>) -> bool {
+        match outcome {
+            Ok(resp) => matches!(resp.status, 429 | 502 | 503 | 504),
+            Err(HttpClientError::Timeout) => true,
+            Err(HttpClientError::RequestFailed(e)) => e.is_connect() || e.is_timeout(),
+            Err(_) => false,
         }
-        
-        response
     }
 }
 
-// 重试中间件
-pub struct RetryMiddleware {
-    max_retries: usize,
-    retry_delay: Duration,
+/// Picks the per-attempt wait shape. `Fixed` waits the same `delay` on every
+/// retry; `Exponential` is the Kubernetes client-go `URLBackoff` shape —
+/// `initial_delay * multiplier^attempt`, clamped to `max_delay`.
+#[derive(Debug, Clone, Copy)]
+pub enum BackoffStrategy {
+    Fixed { delay: Duration },
+    Exponential { initial_delay: Duration, max_delay: Duration, multiplier: f64 },
 }
 
-impl RetryMiddleware {
-    pub fn new(max_retries: usize, retry_delay: Duration) -> Self {
-        Self { max_retries, retry_delay }
+/// `strategy` decides the unjittered delay for attempt `n`; when `jitter` is
+/// set, the actual wait is a uniformly random duration in `[0, delay]`
+/// ("full jitter") rather than `delay` itself, so concurrent clients retrying
+/// the same failure don't all wake up in lockstep.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_retries: usize,
+    pub strategy: BackoffStrategy,
+    pub jitter: bool,
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: usize, base_delay: Duration, multiplier: f64, cap: Duration) -> Self {
+        Self {
+            max_retries,
+            strategy: BackoffStrategy::Exponential { initial_delay: base_delay, max_delay: cap, multiplier },
+            jitter: true,
+        }
+    }
+
+    /// The cap `Retry-After` is clamped to: `max_delay` for `Exponential`,
+    /// or `delay` itself for `Fixed` (there's no separate ceiling to honor).
+    fn max_delay(&self) -> Duration {
+        match self.strategy {
+            BackoffStrategy::Fixed { delay } => delay,
+            BackoffStrategy::Exponential { max_delay, .. } => max_delay,
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        let computed = match self.strategy {
+            BackoffStrategy::Fixed { delay } => delay,
+            BackoffStrategy::Exponential { initial_delay, max_delay, multiplier } => {
+                initial_delay.mul_f64(multiplier.powi(attempt as i32)).min(max_delay)
+            }
+        };
+
+        if self.jitter {
+            let jittered = rand::thread_rng().gen_range(0.0..=computed.as_secs_f64());
+            Duration::from_secs_f64(jittered)
+        } else {
+            computed
+        }
     }
 }
 
-#[async_trait]
-impl Middleware for RetryMiddleware {
-    async fn handle(&self, request: &mut HttpRequest, next: Next<'_>) -> Result<HttpResponse> {
-        let mut attempts = 0;
-        
-        loop {
-            let response = next.clone().run(request).await;
-            
-            match response {
-                Ok(resp) if resp.is_success() => return Ok(resp),
-                Ok(resp) if attempts < self.max_retries => {
-                    warn!("Request failed with status {}, retrying... (attempt {}/{})", 
-                          resp.status, attempts + 1, self.max_retries);
-                    attempts += 1;
-                    tokio::time::sleep(self.retry_delay).await;
-                    continue;
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            strategy: BackoffStrategy::Exponential {
+                initial_delay: Duration::from_millis(200),
+                max_delay: Duration::from_secs(30),
+                multiplier: 2.0,
+            },
+            jitter: true,
+        }
+    }
+}
+
+/// Parses a `Retry-After` header (either delta-seconds or an HTTP-date) into
+/// a concrete delay, clamped to `cap`. Returns `None` if the header is
+/// absent or unparseable, in which case the caller should fall back to the
+/// policy's computed backoff.
+fn retry_after_delay(response: &HttpResponse, cap: Duration) -> Option<Duration> {
+    let header = response.headers.get("Retry-After").or_else(|| response.headers.get("retry-after"))?;
+    let header = header.trim();
+
+    if let Ok(delta_secs) = header.parse::<u64>() {
+        return Some(Duration::from_secs(delta_secs).min(cap));
+    }
+
+    let when = httpdate::parse_http_date(header).ok()?;
+    let delay = when.duration_since(std::time::SystemTime::now()).unwrap_or(Duration::ZERO);
+    Some(delay.min(cap))
+}
+
+pub struct RetryService {
+    inner: BoxedService,
+    policy: RetryPolicy,
+    classifier: Arc<dyn RetryableClassifier>,
+}
+
+impl Service for RetryService {
+    type Response = HttpResponse;
+    type Error = HttpClientError;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        Box::pin(async move {
+            let mut attempt: u32 = 0;
+
+            loop {
+                // Re-drive readiness before every attempt: the pool may have
+                // become saturated while we were sleeping between retries.
+                std::future::poll_fn(|cx| self.inner.poll_ready(cx)).await?;
+                let outcome = self.inner.call(req.clone()).await;
+
+                if !self.classifier.should_retry(&outcome) || attempt >= self.policy.max_retries as u32 {
+                    return outcome;
                 }
-                Ok(resp) => return Ok(resp),
-                Err(e) if attempts < self.max_retries => {
-                    warn!("Request failed with error {}, retrying... (attempt {}/{})", 
-                          e, attempts + 1, self.max_retries);
-                    attempts += 1;
-                    tokio::time::sleep(self.retry_delay).await;
-                    continue;
+
+                let delay = match &outcome {
+                    Ok(resp) if matches!(resp.status, 429 | 503) => {
+                        retry_after_delay(resp, self.policy.max_delay()).unwrap_or_else(|| self.policy.backoff(attempt))
+                    }
+                    _ => self.policy.backoff(attempt),
+                };
+
+                match &outcome {
+                    Ok(resp) => warn!("Request failed with status {}, retrying in {:?} (attempt {}/{})",
+                                       resp.status, delay, attempt + 1, self.policy.max_retries),
+                    Err(e) => warn!("Request failed with error {}, retrying in {:?} (attempt {}/{})",
+                                     e, delay, attempt + 1, self.policy.max_retries),
                 }
-                Err(e) => return Err(e),
+
+                attempt += 1;
+                tokio::time::sleep(delay).await;
             }
-        }
+        })
+    }
+}
+
+pub struct RetryLayer {
+    policy: RetryPolicy,
+    classifier: Arc<dyn RetryableClassifier>,
+}
+
+impl RetryLayer {
+    pub fn new(policy: RetryPolicy) -> Self {
+        Self { policy, classifier: Arc::new(DefaultRetryableClassifier) }
+    }
+
+    pub fn with_classifier(policy: RetryPolicy, classifier: Arc<dyn RetryableClassifier>) -> Self {
+        Self { policy, classifier }
     }
 }
 
-// 超时中间件
-pub struct TimeoutMiddleware {
+impl Layer<BoxedService> for RetryLayer {
+    type Service = BoxedService;
+
+    fn layer(&self, inner: BoxedService) -> BoxedService {
+        Box::new(RetryService {
+            inner,
+            policy: self.policy,
+            classifier: self.classifier.clone(),
+        })
+    }
+}
+
+// ============================================================================
+// Timeout layer
+// ============================================================================
+
+pub struct TimeoutService {
+    inner: BoxedService,
     timeout: Duration,
 }
 
-impl TimeoutMiddleware {
+impl Service for TimeoutService {
+    type Response = HttpResponse;
+    type Error = HttpClientError;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        let timeout = req.timeout.unwrap_or(self.timeout);
+        let fut = self.inner.call(req);
+
+        Box::pin(async move {
+            match tokio::time::timeout(timeout, fut).await {
+                Ok(result) => result,
+                Err(_) => Err(HttpClientError::Timeout),
+            }
+        })
+    }
+}
+
+pub struct TimeoutLayer {
+    timeout: Duration,
+}
+
+impl TimeoutLayer {
     pub fn new(timeout: Duration) -> Self {
         Self { timeout }
     }
 }
 
-#[async_trait]
-impl Middleware for TimeoutMiddleware {
-    async fn handle(&self, request: &mut HttpRequest, next: Next<'_>) -> Result<HttpResponse> {
-        let timeout = request.timeout.unwrap_or(self.timeout);
-        
-        match tokio::time::timeout(timeout, next.run(request)).await {
-            Ok(result) => result,
-            Err(_) => Err(HttpClientError::Timeout),
-        }
+impl Layer<BoxedService> for TimeoutLayer {
+    type Service = BoxedService;
+
+    fn layer(&self, inner: BoxedService) -> BoxedService {
+        Box::new(TimeoutService { inner, timeout: self.timeout })
     }
 }
 
-// 认证中间件
-pub struct AuthMiddleware {
+// ============================================================================
+// Auth layer
+// ============================================================================
+
+pub struct AuthService {
+    inner: BoxedService,
     token: String,
 }
 
-impl AuthMiddleware {
+impl Service for AuthService {
+    type Response = HttpResponse;
+    type Error = HttpClientError;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        req.headers.insert("Authorization".to_string(), format!("Bearer {}", self.token));
+        self.inner.call(req)
+    }
+}
+
+pub struct AuthLayer {
+    token: String,
+}
+
+impl AuthLayer {
     pub fn bearer(token: &str) -> Self {
         Self { token: token.to_string() }
     }
 }
 
-#[async_trait]
-impl Middleware for AuthMiddleware {
-    async fn handle(&self, request: &mut HttpRequest, next: Next<'_>) -> Result<HttpResponse> {
-        request.headers.insert(
-            "Authorization".to_string(),
-            format!("Bearer {}", self.token)
-        );
-        next.run(request).await
+impl Layer<BoxedService> for AuthLayer {
+    type Service = BoxedService;
+
+    fn layer(&self, inner: BoxedService) -> BoxedService {
+        Box::new(AuthService { inner, token: self.token.clone() })
     }
 }
 
-// 连接池
-pub struct ConnectionPool {
-    semaphore: Arc<Semaphore>,
-    client: ReqwestClient,
+// ============================================================================
+// Rate limit layer
+// ============================================================================
+//
+// The connection pool's semaphore (reserved by `PoolService`) caps how many requests can be *in flight* at
+// once; it says nothing about how often new ones may *start*. `RateLimiter`
+// is a token bucket (client-go's `GetRateLimiter` does the same thing) that
+// gates that separately — `requests_per_second` tokens refill continuously
+// up to a `burst` ceiling, and starting a request spends one. Placed as the
+// innermost layer (right next to `PoolService`), so every request pays the
+// rate-limit wait before it ever reserves a pool permit.
+
+struct RateLimiterState {
+    tokens: f64,
+    last_refill: std::time::Instant,
 }
 
-impl ConnectionPool {
-    pub fn new(max_connections: usize) -> Self {
-        let client = ReqwestClient::builder()
-            .pool_max_idle_per_host(max_connections)
-            .pool_idle_timeout(Duration::from_secs(30))
-            .build()
-            .expect("Failed to create HTTP client");
+/// Shared (via `Arc`) across every clone of an `HttpClient`, so they draw
+/// down one budget rather than each enforcing their own.
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    state: Mutex<RateLimiterState>,
+}
 
+impl RateLimiter {
+    pub fn new(requests_per_second: f64, burst: usize) -> Self {
         Self {
-            semaphore: Arc::new(Semaphore::new(max_connections)),
-            client,
+            capacity: burst as f64,
+            refill_per_sec: requests_per_second,
+            state: Mutex::new(RateLimiterState { tokens: burst as f64, last_refill: std::time::Instant::now() }),
         }
     }
 
-    pub async fn execute(&self, request: &HttpRequest) -> Result<HttpResponse> {
-        let _permit = self.semaphore.acquire().await
-            .map_err(|_| HttpClientError::PoolExhausted)?;
+    /// Waits until a token is available, refilling the bucket for the time
+    /// elapsed since it was last checked. The lock is never held across the
+    /// `sleep`, so a cancelled `acquire` (e.g. the caller was dropped) never
+    /// leaves a token spent that nobody collected.
+    async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().await;
+                let now = std::time::Instant::now();
+                let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+                state.tokens = (state.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+                state.last_refill = now;
+
+                if state.tokens >= 1.0 {
+                    state.tokens -= 1.0;
+                    None
+                } else {
+                    let deficit = 1.0 - state.tokens;
+                    Some(Duration::from_secs_f64(deficit / self.refill_per_sec))
+                }
+            };
 
-        let mut req_builder = self.client.request(request.method.clone(), request.url.clone());
+            match wait {
+                None => return,
+                Some(duration) => tokio::time::sleep(duration).await,
+            }
+        }
+    }
+}
 
-        // 添加请求头
-        for (key, value) in &request.headers {
-            req_builder = req_builder.header(key, value);
+/// Mirrors `PoolService`'s poll/future pattern: the acquire future is
+/// polled (not awaited) from `poll_ready`, so a caller blocked on the rate
+/// limit reports `Pending` instead of stalling inside `call`.
+pub struct RateLimitService {
+    inner: BoxedService,
+    limiter: Arc<RateLimiter>,
+    acquiring: Option<BoxFuture<'static, ()>>,
+    have_token: bool,
+}
+
+impl Service for RateLimitService {
+    type Response = HttpResponse;
+    type Error = HttpClientError;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        if !self.have_token {
+            let fut = self.acquiring.get_or_insert_with(|| {
+                let limiter = self.limiter.clone();
+                Box::pin(async move { limiter.acquire().await })
+            });
+
+            match fut.as_mut().poll(cx) {
+                Poll::Ready(()) => {
+                    self.acquiring = None;
+                    self.have_token = true;
+                }
+                Poll::Pending => return Poll::Pending,
+            }
         }
 
-        // 添加请求体
-        if let Some(body) = &request.body {
-            req_builder = req_builder.body(body.clone());
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        self.have_token = false;
+        self.inner.call(req)
+    }
+}
+
+pub struct RateLimitLayer {
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitLayer {
+    pub fn new(limiter: Arc<RateLimiter>) -> Self {
+        Self { limiter }
+    }
+}
+
+impl Layer<BoxedService> for RateLimitLayer {
+    type Service = BoxedService;
+
+    fn layer(&self, inner: BoxedService) -> BoxedService {
+        Box::new(RateLimitService { inner, limiter: self.limiter.clone(), acquiring: None, have_token: false })
+    }
+}
+
+// ============================================================================
+// Redirect layer
+// ============================================================================
+
+/// Controls how 3xx responses are followed. The underlying reqwest client
+/// used by `PoolService` always disables its own redirect policy (see
+/// `HttpClientBuilder::build`), so this middleware has full visibility into
+/// `Location` headers instead of redirects happening invisibly below the
+/// rest of the pipeline. `None` returns a 3xx response to the caller as-is;
+/// `Limited(n)` follows up to `n` hops (reqwest itself also defaults to 10)
+/// before giving up with `HttpClientError::MiddlewareError`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedirectPolicy {
+    None,
+    Limited(usize),
+}
+
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        Self::Limited(10)
+    }
+}
+
+/// Headers that must never cross to a different host or scheme, mirroring
+/// how well-behaved clients avoid leaking credentials that `AuthMiddleware`
+/// (or a caller) attached to the original request.
+const SENSITIVE_REDIRECT_HEADERS: &[&str] = &["authorization", "cookie", "proxy-authorization"];
+
+pub struct RedirectService {
+    inner: BoxedService,
+    max_hops: usize,
+}
+
+impl Service for RedirectService {
+    type Response = HttpResponse;
+    type Error = HttpClientError;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        Box::pin(async move {
+            let mut current = req;
+            let mut hops = 0usize;
+
+            loop {
+                // Re-running the full pipeline on every hop (rather than
+                // just re-issuing the raw connection) means logging, retry,
+                // and auth all see each redirected request individually.
+                std::future::poll_fn(|cx| self.inner.poll_ready(cx)).await?;
+                let response = self.inner.call(current.clone()).await?;
+
+                if !(300..400).contains(&response.status) {
+                    return Ok(response);
+                }
+
+                let Some(location) = response.headers.get("Location").or_else(|| response.headers.get("location")) else {
+                    return Ok(response);
+                };
+
+                if hops >= self.max_hops {
+                    return Err(HttpClientError::MiddlewareError(format!(
+                        "exceeded {} redirect hop(s) while fetching {}",
+                        self.max_hops, current.url
+                    )));
+                }
+
+                let next_url = current.url.join(location)
+                    .map_err(|e| HttpClientError::UrlParseError(e.to_string()))?;
+                let cross_origin = next_url.host_str() != current.url.host_str()
+                    || next_url.scheme() != current.url.scheme();
+
+                let mut next = current.clone();
+                next.url = next_url;
+
+                // 301/302/303 downgrade to GET and drop the body per spec;
+                // 307/308 preserve the original method and body.
+                if matches!(response.status, 301 | 302 | 303) {
+                    next.method = Method::GET;
+                    next.body = None;
+                    next.headers.remove("Content-Type");
+                }
+
+                if cross_origin {
+                    next.headers.retain(|k, _| !SENSITIVE_REDIRECT_HEADERS.contains(&k.to_lowercase().as_str()));
+                }
+
+                current = next;
+                hops += 1;
+            }
+        })
+    }
+}
+
+pub struct RedirectLayer {
+    max_hops: usize,
+}
+
+impl RedirectLayer {
+    pub fn new(max_hops: usize) -> Self {
+        Self { max_hops }
+    }
+}
+
+impl Layer<BoxedService> for RedirectLayer {
+    type Service = BoxedService;
+
+    fn layer(&self, inner: BoxedService) -> BoxedService {
+        Box::new(RedirectService { inner, max_hops: self.max_hops })
+    }
+}
+
+// ============================================================================
+// Ergonomic async middleware: Middleware/Next over Service/Layer
+// ============================================================================
+//
+// `Service`/`Layer` give the stack real backpressure, but writing one means
+// hand-rolling `poll_ready`/`call` and boxing the future yourself. Most
+// middleware don't need poll-level backpressure control — they just want to
+// inspect/modify a request, decide whether to forward it, and inspect/modify
+// the response on the way back. `Middleware` is sugar for that common case:
+// implementers write a single `async fn handle`, and `#[async_trait]` boxes
+// the future for them. `MiddlewareLayer`/`MiddlewareService` adapt it into
+// the `Service`/`Layer` world so it can sit in the same `layers` stack as
+// `LoggingLayer`/`RetryLayer`/etc.
+
+/// The remaining stack plus the final transport call, handed to a
+/// [`Middleware`] so it can forward a request downstream.
+pub struct Next<'a> {
+    inner: &'a mut BoxedService,
+}
+
+impl<'a> Next<'a> {
+    pub async fn run(self, req: HttpRequest) -> Result<HttpResponse> {
+        ready_and_call(self.inner, req).await
+    }
+}
+
+/// An async middleware that can inspect/modify a request, forward it via
+/// `next.run(req).await`, then inspect/modify the response — or skip `next`
+/// entirely and produce a response itself, e.g. for caching, mocking, or a
+/// circuit breaker.
+#[async_trait]
+pub trait Middleware: Send + Sync {
+    async fn handle(&self, req: HttpRequest, next: Next<'_>) -> Result<HttpResponse>;
+}
+
+pub struct MiddlewareService {
+    inner: BoxedService,
+    middleware: Arc<dyn Middleware>,
+}
+
+impl Service for MiddlewareService {
+    type Response = HttpResponse;
+    type Error = HttpClientError;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        // Whether `inner` is even reached depends on the middleware's own
+        // logic (a cache hit never calls `next.run`), so there's nothing
+        // meaningful to report here beyond "always ready" — backpressure
+        // from `inner` is still observed the moment `next.run` does call it.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        let middleware = self.middleware.clone();
+        let next = Next { inner: &mut self.inner };
+        Box::pin(async move { middleware.handle(req, next).await })
+    }
+}
+
+pub struct MiddlewareLayer {
+    middleware: Arc<dyn Middleware>,
+}
+
+impl MiddlewareLayer {
+    pub fn new(middleware: Arc<dyn Middleware>) -> Self {
+        Self { middleware }
+    }
+}
+
+impl Layer<BoxedService> for MiddlewareLayer {
+    type Service = BoxedService;
+
+    fn layer(&self, inner: BoxedService) -> BoxedService {
+        Box::new(MiddlewareService { inner, middleware: self.middleware.clone() })
+    }
+}
+
+/// Caches successful GET responses for `ttl`, short-circuiting `next.run`
+/// entirely on a hit — the concrete illustration of why `Middleware` lets a
+/// handler skip `next` rather than always being a pre/post hook pair.
+/// Anything other than a GET, or a GET whose entry has expired, falls
+/// through to `next.run` as normal; a fresh miss is stored before the
+/// response is returned.
+pub struct CachingMiddleware {
+    ttl: Duration,
+    entries: std::sync::Mutex<HashMap<String, CachedResponse>>,
+}
+
+/// A `Clone`-friendly snapshot of the parts of `HttpResponse` worth caching.
+/// `HttpResponse` itself isn't `Clone` (its `body_stream` can't be), so a hit
+/// is served by reconstructing one from this instead.
+#[derive(Clone)]
+struct CachedResponse {
+    status: u16,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+    cached_at: std::time::Instant,
+}
+
+impl CachingMiddleware {
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, entries: std::sync::Mutex::new(HashMap::new()) }
+    }
+
+    /// Only `GET` requests are cacheable; anything else defers to `next`.
+    fn cache_key(req: &HttpRequest) -> Option<String> {
+        if req.method == Method::GET {
+            Some(req.url.to_string())
+        } else {
+            None
         }
+    }
+}
 
-        // 设置超时
-        if let Some(timeout) = request.timeout {
-            req_builder = req_builder.timeout(timeout);
+#[async_trait]
+impl Middleware for CachingMiddleware {
+    async fn handle(&self, req: HttpRequest, next: Next<'_>) -> Result<HttpResponse> {
+        let Some(key) = Self::cache_key(&req) else {
+            return next.run(req).await;
+        };
+
+        if let Some(cached) = self.entries.lock().unwrap().get(&key) {
+            if cached.cached_at.elapsed() < self.ttl {
+                return Ok(HttpResponse {
+                    status: cached.status,
+                    headers: cached.headers.clone(),
+                    body: cached.body.clone(),
+                    body_stream: None,
+                });
+            }
         }
 
-        let response = req_builder.send().await?;
-        
-        let status = response.status().as_u16();
-        let headers = response.headers()
-            .iter()
-            .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
-            .collect();
-        let body = response.bytes().await?.to_vec();
+        let response = next.run(req).await?;
 
-        Ok(HttpResponse {
-            status,
-            headers,
-            body,
-        })
+        if response.is_success() {
+            self.entries.lock().unwrap().insert(key, CachedResponse {
+                status: response.status,
+                headers: response.headers.clone(),
+                body: response.body.clone(),
+                cached_at: std::time::Instant::now(),
+            });
+        }
+
+        Ok(response)
     }
 }
 
+// ============================================================================
 // HTTP客户端
+// ============================================================================
+
 pub struct HttpClient {
-    pool: Arc<ConnectionPool>,
-    middlewares: Vec<Arc<dyn Middleware>>,
+    client: ReqwestClient,
+    semaphore: Arc<Semaphore>,
+    layers: Vec<Arc<dyn Layer<BoxedService, Service = BoxedService> + Send + Sync>>,
 }
 
 impl HttpClient {
     pub fn new() -> Self {
-        Self {
-            pool: Arc::new(ConnectionPool::new(10)),
-            middlewares: Vec::new(),
-        }
+        HttpClientBuilder::new().build().expect("default client config should never fail to build")
     }
 
     pub fn with_pool(max_connections: usize) -> Self {
-        Self {
-            pool: Arc::new(ConnectionPool::new(max_connections)),
-            middlewares: Vec::new(),
-        }
-    }
-
-    pub fn with_middleware(mut self, middleware: Arc<dyn Middleware>) -> Self {
-        self.middlewares.push(middleware);
-        self
+        HttpClientBuilder::new()
+            .max_connections(max_connections)
+            .build()
+            .expect("default client config should never fail to build")
     }
 
-    pub async fn request(&self, mut request: HttpRequest) -> Result<HttpResponse> {
-        let next = Next {
-            middlewares: &self.middlewares,
-            index: 0,
-            client: self,
-        };
-        
-        next.run(&mut request).await
+    /// Builds a fresh service stack for this request from the client's
+    /// cheaply-cloneable pieces, then drives it through `poll_ready`/`call`.
+    /// Layers are folded in reverse so the first one added via the builder
+    /// ends up outermost, matching the old `Next`-based ordering where
+    /// `middlewares[0]` ran first.
+    pub async fn request(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let mut svc: BoxedService = Box::new(PoolService::new(self.client.clone(), self.semaphore.clone()));
+        for layer in self.layers.iter().rev() {
+            svc = layer.layer(svc);
+        }
+        ready_and_call(&mut svc, request).await
     }
 
-    async fn execute_request(&self, request: &HttpRequest) -> Result<HttpResponse> {
-        self.pool.execute(request).await
+    /// Like [`request`](HttpClient::request), but returns as soon as the
+    /// status and headers are available and exposes the body as a
+    /// `Stream<Item = Result<Bytes>>` instead of buffering it. The request
+    /// still goes through the full logging/retry/timeout/auth stack — only
+    /// `PoolService` behaves differently, skipping the `.bytes().await`
+    /// buffering step.
+    pub async fn stream(&self, request: HttpRequest) -> Result<HttpResponseStream> {
+        let response = self.request(request.streaming()).await?;
+        let body = response.body_stream.expect("streaming request must produce a body_stream");
+
+        Ok(HttpResponseStream {
+            status: response.status,
+            headers: response.headers,
+            body,
+        })
     }
 
     // 便捷方法
@@ -370,6 +1315,63 @@ impl HttpClient {
         let request = HttpRequest::new(Method::DELETE, url)?;
         self.request(request).await
     }
+
+    /// Follows a paginated API and yields one deserialized item at a time.
+    ///
+    /// Each page's body is expected to be a top-level JSON array; use
+    /// [`HttpClient::paginate_field`] if the array is nested under a field.
+    /// The stream is lazy: the next page is only requested once the consumer
+    /// has polled past every item already buffered from the current page,
+    /// and each page request goes through [`HttpClient::request`] so it gets
+    /// the full middleware stack. Pagination stops when the response has no
+    /// `Link: <...>; rel="next"` header; any `HttpClientError` is yielded as
+    /// a final `Err` item that ends the stream.
+    pub fn paginate<T>(&self, request: HttpRequest) -> impl Stream<Item = Result<T>> + '_
+    where
+        T: for<'de> Deserialize<'de> + 'static,
+    {
+        self.paginate_field(request, None)
+    }
+
+    /// Like [`paginate`](HttpClient::paginate), but reads the page's items
+    /// from a named field of the response body instead of the body's
+    /// top level (e.g. `{"items": [...], "cursor": "..."}`).
+    pub fn paginate_field<T>(&self, request: HttpRequest, field: Option<&str>) -> impl Stream<Item = Result<T>> + '_
+    where
+        T: for<'de> Deserialize<'de> + 'static,
+    {
+        let field = field.map(|f| f.to_string());
+        let state = PaginationState {
+            items: VecDeque::new(),
+            next_request: Some(request),
+        };
+
+        try_unfold(state, move |mut state| {
+            let field = field.clone();
+            async move {
+                loop {
+                    if let Some(item) = state.items.pop_front() {
+                        return Ok(Some((item, state)));
+                    }
+
+                    let request = match state.next_request.take() {
+                        Some(request) => request,
+                        None => return Ok(None),
+                    };
+
+                    let response = self.request(request.clone()).await?;
+                    state.next_request = next_page_request(&response, &request)?;
+
+                    let page: Vec<T> = extract_page(&response.body, field.as_deref())?;
+                    state.items = page.into_iter().collect();
+
+                    if state.items.is_empty() && state.next_request.is_none() {
+                        return Ok(None);
+                    }
+                }
+            }
+        })
+    }
 }
 
 impl Default for HttpClient {
@@ -378,17 +1380,131 @@ impl Default for HttpClient {
     }
 }
 
+/// A single proxy endpoint: the address reqwest dials, plus optional
+/// credentials sent as a `Proxy-Authorization` header on every request that
+/// goes through it.
+#[derive(Debug, Clone)]
+pub struct Proxy {
+    pub url: String,
+    pub credentials: Option<(String, String)>,
+}
+
+impl Proxy {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into(), credentials: None }
+    }
+
+    pub fn with_credentials(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.credentials = Some((username.into(), password.into()));
+        self
+    }
+
+    fn apply_to(&self, mut proxy: reqwest::Proxy) -> reqwest::Proxy {
+        if let Some((username, password)) = &self.credentials {
+            proxy = proxy.basic_auth(username, password);
+        }
+        proxy
+    }
+}
+
+/// Per-scheme proxy configuration for `HttpClientBuilder::proxy`. Supports
+/// distinct http/https/socks5 proxies (each with its own credentials) and a
+/// `no_proxy` exclusion list, built explicitly or via [`ProxyConfig::from_env`].
+#[derive(Debug, Clone, Default)]
+pub struct ProxyConfig {
+    pub http_proxy: Option<Proxy>,
+    pub https_proxy: Option<Proxy>,
+    pub socks5_proxy: Option<Proxy>,
+    pub no_proxy: Vec<String>,
+}
+
+impl ProxyConfig {
+    /// Reads `HTTP_PROXY`/`HTTPS_PROXY`/`ALL_PROXY`/`NO_PROXY`, preferring
+    /// each variable's lowercase form (curl's convention — some CGI setups
+    /// let a client-controlled request header leak into the uppercase
+    /// `HTTP_PROXY`) and falling back to `ALL_PROXY` for either scheme when
+    /// its own variable isn't set.
+    pub fn from_env() -> Self {
+        fn read(name: &str) -> Option<String> {
+            std::env::var(name.to_lowercase()).or_else(|_| std::env::var(name)).ok()
+        }
+
+        let all_proxy = read("ALL_PROXY");
+        let no_proxy = read("NO_PROXY")
+            .map(|v| v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+
+        Self {
+            http_proxy: read("HTTP_PROXY").or_else(|| all_proxy.clone()).map(Proxy::new),
+            https_proxy: read("HTTPS_PROXY").or_else(|| all_proxy.clone()).map(Proxy::new),
+            socks5_proxy: None,
+            no_proxy,
+        }
+    }
+
+    fn apply(&self, mut builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder> {
+        // Building a `ProxyConfig` at all — `from_env` included — opts out
+        // of reqwest's own environment probing, since the two would
+        // otherwise silently stack.
+        builder = builder.no_proxy();
+
+        let no_proxy = if self.no_proxy.is_empty() {
+            None
+        } else {
+            reqwest::NoProxy::from_string(&self.no_proxy.join(","))
+        };
+
+        if let Some(proxy) = &self.http_proxy {
+            let mut reqwest_proxy = proxy.apply_to(reqwest::Proxy::http(&proxy.url)?);
+            if let Some(no_proxy) = no_proxy.clone() {
+                reqwest_proxy = reqwest_proxy.no_proxy(Some(no_proxy));
+            }
+            builder = builder.proxy(reqwest_proxy);
+        }
+
+        if let Some(proxy) = &self.https_proxy {
+            let mut reqwest_proxy = proxy.apply_to(reqwest::Proxy::https(&proxy.url)?);
+            if let Some(no_proxy) = no_proxy.clone() {
+                reqwest_proxy = reqwest_proxy.no_proxy(Some(no_proxy));
+            }
+            builder = builder.proxy(reqwest_proxy);
+        }
+
+        if let Some(proxy) = &self.socks5_proxy {
+            let mut reqwest_proxy = proxy.apply_to(reqwest::Proxy::all(&proxy.url)?);
+            if let Some(no_proxy) = no_proxy {
+                reqwest_proxy = reqwest_proxy.no_proxy(Some(no_proxy));
+            }
+            builder = builder.proxy(reqwest_proxy);
+        }
+
+        Ok(builder)
+    }
+}
+
 // 客户端构建器
 pub struct HttpClientBuilder {
     max_connections: usize,
-    middlewares: Vec<Arc<dyn Middleware>>,
+    layers: Vec<Arc<dyn Layer<BoxedService, Service = BoxedService> + Send + Sync>>,
+    proxy: Option<ProxyConfig>,
+    root_certificates: Vec<Vec<u8>>,
+    client_identity: Option<(Vec<u8>, String)>,
+    redirect_policy: RedirectPolicy,
+    connect_timeout: Option<Duration>,
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl HttpClientBuilder {
     pub fn new() -> Self {
         Self {
             max_connections: 10,
-            middlewares: Vec::new(),
+            layers: Vec::new(),
+            proxy: None,
+            root_certificates: Vec::new(),
+            client_identity: None,
+            redirect_policy: RedirectPolicy::default(),
+            connect_timeout: None,
+            rate_limiter: None,
         }
     }
 
@@ -398,31 +1514,136 @@ impl HttpClientBuilder {
     }
 
     pub fn with_logging(mut self) -> Self {
-        self.middlewares.push(Arc::new(LoggingMiddleware));
+        self.layers.push(Arc::new(LoggingLayer));
         self
     }
 
-    pub fn with_retry(mut self, max_retries: usize, delay: Duration) -> Self {
-        self.middlewares.push(Arc::new(RetryMiddleware::new(max_retries, delay)));
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.layers.push(Arc::new(RetryLayer::new(policy)));
         self
     }
 
+    /// Bounds the total time of each call through the layers below this one,
+    /// from sending the request (connection set-up included) to a complete
+    /// response. It is not a separate read timeout: connecting counts against
+    /// it too. [`connect_timeout`](Self::connect_timeout) additionally bounds
+    /// the connection step on its own.
     pub fn with_timeout(mut self, timeout: Duration) -> Self {
-        self.middlewares.push(Arc::new(TimeoutMiddleware::new(timeout)));
+        self.layers.push(Arc::new(TimeoutLayer::new(timeout)));
+        self
+    }
+
+    /// Bounds how long establishing the underlying TCP/TLS connection may
+    /// take, separately from [`with_timeout`](Self::with_timeout)'s read
+    /// timeout. Unlike the read timeout this applies to the whole client
+    /// (reqwest has no per-request connect timeout), so it's a builder
+    /// setting rather than a `TimeoutLayer`.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Throttles how often new requests may start to `requests_per_second`,
+    /// with bursts up to `burst` — independent of the concurrency cap the
+    /// connection pool's semaphore enforces. See [`RateLimiter`].
+    pub fn rate_limit(mut self, requests_per_second: f64, burst: usize) -> Self {
+        self.rate_limiter = Some(Arc::new(RateLimiter::new(requests_per_second, burst)));
         self
     }
 
     pub fn with_auth(mut self, token: &str) -> Self {
-        self.middlewares.push(Arc::new(AuthMiddleware::bearer(token)));
+        self.layers.push(Arc::new(AuthLayer::bearer(token)));
         self
     }
 
-    pub fn build(self) -> HttpClient {
-        let mut client = HttpClient::with_pool(self.max_connections);
-        for middleware in self.middlewares {
-            client = client.with_middleware(middleware);
+    /// Inserts a [`Middleware`] into the stack, in the position this call
+    /// was made relative to the other `with_*` calls (outermost first).
+    pub fn with_middleware(mut self, middleware: Arc<dyn Middleware>) -> Self {
+        self.layers.push(Arc::new(MiddlewareLayer::new(middleware)));
+        self
+    }
+
+    /// Caches successful `GET` responses for `ttl`. See [`CachingMiddleware`].
+    pub fn with_cache(self, ttl: Duration) -> Self {
+        self.with_middleware(Arc::new(CachingMiddleware::new(ttl)))
+    }
+
+    /// Routes every request in this client through `config` — a corporate
+    /// HTTP/HTTPS/SOCKS5 proxy, for example.
+    pub fn proxy(mut self, config: ProxyConfig) -> Self {
+        self.proxy = Some(config);
+        self
+    }
+
+    /// Trusts an additional root certificate (PEM-encoded), for talking to
+    /// servers with a self-signed or internal-CA certificate.
+    pub fn add_root_certificate(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.root_certificates.push(pem.into());
+        self
+    }
+
+    /// Presents `identity` (PKCS#12 (`.p12`/`.pfx`) bytes, unlocked with
+    /// `password`) as the client certificate for mutual-TLS authentication.
+    pub fn client_identity(mut self, identity: impl Into<Vec<u8>>, password: impl Into<String>) -> Self {
+        self.client_identity = Some((identity.into(), password.into()));
+        self
+    }
+
+    /// Sets how 3xx responses are followed (default `Limited(10)` if this is
+    /// never called). See [`RedirectPolicy`].
+    pub fn with_redirect_policy(mut self, policy: RedirectPolicy) -> Self {
+        self.redirect_policy = policy;
+        self
+    }
+
+    pub fn build(self) -> Result<HttpClient> {
+        let mut reqwest_builder = ReqwestClient::builder()
+            .pool_max_idle_per_host(self.max_connections)
+            .pool_idle_timeout(Duration::from_secs(30))
+            // Redirects are handled by `RedirectService` instead, so they
+            // run through the rest of the middleware stack on every hop.
+            .redirect(reqwest::redirect::Policy::none());
+
+        if let Some(connect_timeout) = self.connect_timeout {
+            reqwest_builder = reqwest_builder.connect_timeout(connect_timeout);
+        }
+
+        if let Some(proxy) = &self.proxy {
+            reqwest_builder = proxy.apply(reqwest_builder)?;
+        }
+
+        for pem in &self.root_certificates {
+            reqwest_builder = reqwest_builder.add_root_certificate(reqwest::Certificate::from_pem(pem)?);
         }
-        client
+
+        if let Some((bytes, password)) = &self.client_identity {
+            let identity = reqwest::Identity::from_pkcs12_der(bytes, password)?;
+            reqwest_builder = reqwest_builder.identity(identity);
+        }
+
+        let client = reqwest_builder.build()?;
+
+        // The redirect layer always goes outermost (front of the list,
+        // since `request` folds in reverse) so a followed redirect re-enters
+        // logging/retry/timeout/auth exactly like a fresh request would.
+        let mut layers = self.layers;
+
+        // Pushed last so it's processed first by `request`'s reverse fold,
+        // landing right next to `PoolService` — every request pays the
+        // rate-limit wait before it ever reserves a pool permit.
+        if let Some(limiter) = self.rate_limiter {
+            layers.push(Arc::new(RateLimitLayer::new(limiter)) as Arc<dyn Layer<BoxedService, Service = BoxedService> + Send + Sync>);
+        }
+
+        if let RedirectPolicy::Limited(max_hops) = self.redirect_policy {
+            layers.insert(0, Arc::new(RedirectLayer::new(max_hops)) as Arc<dyn Layer<BoxedService, Service = BoxedService> + Send + Sync>);
+        }
+
+        Ok(HttpClient {
+            client,
+            semaphore: Arc::new(Semaphore::new(self.max_connections)),
+            layers,
+        })
     }
 }
 
@@ -439,7 +1660,7 @@ async fn main() -> Result<()> {
     // 示例1: 基本用法
     println!("=== 基本HTTP客户端示例 ===");
     let client = HttpClient::new();
-    
+
     match client.get("https://httpbin.org/get").await {
         Ok(response) => {
             println!("Status: {}", response.status);
@@ -453,9 +1674,9 @@ async fn main() -> Result<()> {
     let client_with_middleware = HttpClientBuilder::new()
         .max_connections(5)
         .with_logging()
-        .with_retry(3, Duration::from_millis(500))
+        .with_retry(RetryPolicy::default())
         .with_timeout(Duration::from_secs(10))
-        .build();
+        .build()?;
 
     // POST请求示例
     #[derive(Serialize)]
@@ -510,5 +1731,19 @@ async fn main() -> Result<()> {
         Err(e) => println!("IP request error: {}", e),
     }
 
+    // 示例5: 缓存中间件（短路调用链）
+    println!("\n=== 缓存中间件示例 ===");
+    let cached_client = HttpClientBuilder::new()
+        .with_logging()
+        .with_cache(Duration::from_secs(30))
+        .build()?;
+
+    for attempt in 1..=2 {
+        match cached_client.get("https://httpbin.org/uuid").await {
+            Ok(response) => println!("Attempt {attempt}: {}", response.text()),
+            Err(e) => println!("Attempt {attempt} error: {e}"),
+        }
+    }
+
     Ok(())
 }
@@ -3,12 +3,14 @@
 // ============================================================================
 
 // 标准库导入
-use std::collections::HashMap;    // 标准库的哈希映射，用于存储键值对（如HTTP头部）
-use std::time::Duration;          // 标准库的时间间隔类型，用于设置超时时间
+use std::collections::{HashMap, VecDeque}; // HashMap: 存储键值对（如HTTP头部）；VecDeque: 响应缓存的LRU淘汰顺序
+use std::time::{Duration, Instant};        // Duration: 时间间隔；Instant: 响应缓存判断是否过期用的单调时钟
 use std::sync::Arc;               // 标准库的原子引用计数智能指针，用于多线程间安全共享数据
 
 // Tokio异步运行时相关导入
-use tokio::sync::Semaphore;       // Tokio提供的信号量，用于控制并发连接数（连接池实现）
+use tokio::sync::{Semaphore, Mutex, broadcast}; // Semaphore: 连接池；Mutex: 保护在途请求表；broadcast: 向等待者广播领头请求的结果
+use tokio::io::{AsyncReadExt, AsyncWriteExt};    // 流式上传/下载分块读写文件所需的trait
+use tokio_util::sync::CancellationToken;         // 请求取消令牌：外部调用abort()即可随时中断一次send_cancellable
 
 // Serde序列化框架导入
 use serde::{Deserialize, Serialize}; // Serde库的序列化和反序列化trait，用于JSON数据处理
@@ -19,6 +21,9 @@ use thiserror::Error;             // thiserror库提供的Error derive宏，简
 // 日志库导入
 use log::{info, warn, error};     // log库提供的日志宏，用于记录不同级别的日志信息
 
+// 随机数库导入
+use rand::Rng;                     // rand库的Rng trait，用于生成退避抖动的随机毫秒数
+
 // ============================================================================
 // 错误类型定义
 // ============================================================================
@@ -26,7 +31,9 @@ use log::{info, warn, error};     // log库提供的日志宏，用于记录不
 // #[derive(Error, Debug)] 是属性宏的组合：
 // - Error: 来自thiserror库，自动为枚举实现std::error::Error trait
 // - Debug: 标准库trait，允许使用{:?}格式化输出，用于调试
-#[derive(Error, Debug)]
+// Clone: 请求去重功能需要把同一个结果广播给多个等待者，
+// 而broadcast::Sender要求其负载类型实现Clone，所有字段都是String或无字段，可以安全派生
+#[derive(Error, Debug, Clone)]
 pub enum HttpClientError {
     // #[error("...")] 是thiserror提供的属性宏，定义错误的显示信息
     // {0} 表示元组结构体的第一个字段，用于格式化字符串
@@ -47,6 +54,12 @@ pub enum HttpClientError {
     
     #[error("Serialization error: {0}")]
     SerializationError(String),              // 序列化/反序列化错误，包含错误详情
+
+    #[error("Authentication expired")]
+    AuthExpired,                              // 认证已过期（如token刷新后仍被拒绝），由状态码拦截器产生
+
+    #[error("Request was cancelled")]
+    Cancelled,                                // 请求被CancellationToken取消（调用方主动abort，或被更新的请求取代）
 }
 
 // 类型别名：简化Result类型的使用，T是成功时的类型，错误类型固定为HttpClientError
@@ -286,19 +299,37 @@ pub struct ConnectionGuard {
 // Send + Sync: trait约束，表示实现者必须是线程安全的
 // - Send: 可以在线程间转移所有权
 // - Sync: 可以在多线程间安全共享引用
+// 装箱的Future类型别名：process_request/process_response是同步方法，
+// 但像token刷新这样的场景需要调用异步的刷新接口。Middleware是trait对象（Box<dyn Middleware>），
+// trait方法若直接写成async fn则不是对象安全的，所以用返回装箱Future的方式手动实现"异步方法"
+pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
+
+// on_response的返回值：大多数中间件什么都不用做（Continue）；
+// 需要在特定响应上重放原始请求的中间件（如刷新token后重试一次）返回Replay携带重放用的新请求
+pub enum ResponseAction {
+    Continue,
+    Replay(HttpRequest),
+}
+
 pub trait Middleware: Send + Sync {
     // 返回中间件名称，&str是字符串切片的引用
     fn name(&self) -> &str;
-    
+
     // 处理请求的方法
     // &self: 不可变引用自身
     // request: 可变引用HTTP请求，允许中间件修改请求
     // -> Result<()>: 返回空的Result，()表示成功时无返回值
     fn process_request(&self, request: &mut HttpRequest) -> Result<()>;
-    
+
     // 处理响应的方法
     // response: 可变引用HTTP响应，允许中间件修改响应
     fn process_response(&self, response: &mut HttpResponse) -> Result<()>;
+
+    // 响应到达后、process_response之前调用，用于决定是否需要重放原始请求
+    // 默认什么都不做；只有RefreshAuthMiddleware这类需要异步刷新凭据的中间件才需要覆盖它
+    fn on_response<'a>(&'a self, _request: &'a HttpRequest, _response: &'a HttpResponse) -> BoxFuture<'a, Result<ResponseAction>> {
+        Box::pin(async { Ok(ResponseAction::Continue) })
+    }
 }
 
 // ============================================================================
@@ -438,6 +469,272 @@ impl Middleware for AuthMiddleware {
     }
 }
 
+// ============================================================================
+// Token刷新认证中间件：401/403时自动刷新token并重放一次原始请求
+// ============================================================================
+
+// 刷新函数类型：异步闭包，返回新的bearer token
+type RefreshFn = Arc<dyn Fn() -> BoxFuture<'static, Result<String>> + Send + Sync>;
+
+// 需要跨请求共享、可变的刷新状态：当前token，以及一个"代"计数器
+// generation用于单飞(single-flight)判定：多个并发请求同时收到401时，
+// 只应该有一个真正触发刷新，其余的请求应该等它完成后直接复用新token
+struct RefreshState {
+    token: String,
+    generation: u64,
+}
+
+pub struct RefreshAuthMiddleware {
+    state: std::sync::Mutex<RefreshState>,    // 保护token/generation的读写，process_request只需要快速读一下，不涉及await
+    refresh_lock: tokio::sync::Mutex<()>,     // 保证同一时刻只有一次刷新在执行（跨越await点，必须用tokio的Mutex）
+    refresh_fn: RefreshFn,                    // 用户提供的刷新逻辑
+}
+
+impl RefreshAuthMiddleware {
+    // initial_token: 初始的bearer token
+    // refresh_fn: 刷新token的异步闭包，失败时返回Err会导致本次重放被放弃，原始401/403原样返回给调用者
+    pub fn new(
+        initial_token: String,
+        refresh_fn: impl Fn() -> BoxFuture<'static, Result<String>> + Send + Sync + 'static,
+    ) -> Self {
+        RefreshAuthMiddleware {
+            state: std::sync::Mutex::new(RefreshState { token: initial_token, generation: 0 }),
+            refresh_lock: tokio::sync::Mutex::new(()),
+            refresh_fn: Arc::new(refresh_fn),
+        }
+    }
+}
+
+impl Middleware for RefreshAuthMiddleware {
+    fn name(&self) -> &str {
+        "RefreshAuthMiddleware"
+    }
+
+    // 用当前已知的token给请求签名；是否需要刷新留给on_response处理
+    fn process_request(&self, request: &mut HttpRequest) -> Result<()> {
+        let token = self.state.lock().unwrap().token.clone();
+        request.headers.insert("Authorization".to_string(), format!("Bearer {}", token));
+        Ok(())
+    }
+
+    fn process_response(&self, _response: &mut HttpResponse) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_response<'a>(&'a self, request: &'a HttpRequest, response: &'a HttpResponse) -> BoxFuture<'a, Result<ResponseAction>> {
+        Box::pin(async move {
+            if response.status != 401 && response.status != 403 {
+                return Ok(ResponseAction::Continue);
+            }
+
+            // 记下触发这次401/403时我们以为的token是第几代
+            let observed_generation = self.state.lock().unwrap().generation;
+
+            // 持锁期间做真正的刷新；锁本身保证了同一时刻只有一个任务在调用refresh_fn
+            {
+                let _guard = self.refresh_lock.lock().await;
+                let current_generation = self.state.lock().unwrap().generation;
+                if current_generation == observed_generation {
+                    // generation没有变化，说明还没有别的任务刷新过，该我们真正发起一次刷新
+                    let new_token = match (self.refresh_fn)().await {
+                        Ok(token) => token,
+                        // 刷新失败：放弃重放，原始401/403照常交给process_response并返回给调用者
+                        Err(_) => return Ok(ResponseAction::Continue),
+                    };
+                    let mut state = self.state.lock().unwrap();
+                    state.token = new_token;
+                    state.generation += 1;
+                } // 否则：等锁的这段时间里，别的任务已经刷新过了，直接使用它刷新后的token即可
+            }
+
+            let new_token = self.state.lock().unwrap().token.clone();
+            let mut replay_request = request.clone();
+            replay_request.headers.insert("Authorization".to_string(), format!("Bearer {}", new_token));
+            Ok(ResponseAction::Replay(replay_request))
+        })
+    }
+}
+
+// ============================================================================
+// 状态码拦截中间件：把特定的HTTP状态码映射成类型化的错误
+// ============================================================================
+
+// 状态码处理函数：接收原始响应，产出一个具体的错误类型（如把403映射成AuthExpired）
+type StatusHandler = Arc<dyn Fn(&HttpResponse) -> HttpClientError + Send + Sync>;
+
+pub struct StatusCodeMiddleware {
+    handlers: HashMap<u16, StatusHandler>,
+}
+
+impl StatusCodeMiddleware {
+    pub fn new() -> Self {
+        StatusCodeMiddleware { handlers: HashMap::new() }
+    }
+
+    // 构建器模式：注册某个状态码对应的错误转换函数
+    pub fn on_status(mut self, status: u16, handler: impl Fn(&HttpResponse) -> HttpClientError + Send + Sync + 'static) -> Self {
+        self.handlers.insert(status, Arc::new(handler));
+        self
+    }
+}
+
+impl Middleware for StatusCodeMiddleware {
+    fn name(&self) -> &str {
+        "StatusCodeMiddleware"
+    }
+
+    fn process_request(&self, _request: &mut HttpRequest) -> Result<()> {
+        Ok(())
+    }
+
+    // 命中注册过的状态码时，把响应转换成对应的类型化错误，中断正常的返回路径
+    fn process_response(&self, response: &mut HttpResponse) -> Result<()> {
+        if let Some(handler) = self.handlers.get(&response.status) {
+            return Err(handler(response));
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// 响应缓存子系统：针对GET请求的可选缓存，支持基于ETag/Last-Modified的条件请求重新验证
+// ============================================================================
+
+// 缓存条目：保存命中时需要的一切，以及判断新鲜度和做条件请求需要的验证器
+#[derive(Clone)]
+pub struct CacheEntry {
+    status: u16,
+    headers: HashMap<String, String>,
+    body: String,
+    stored_at: Instant,              // 存入缓存的时刻
+    ttl: Duration,                   // 新鲜度窗口：stored_at + ttl之前都算新鲜
+    etag: Option<String>,            // 来自响应头ETag，用于If-None-Match
+    last_modified: Option<String>,   // 来自响应头Last-Modified，用于If-Modified-Since
+}
+
+impl CacheEntry {
+    // 是否已经过了新鲜度窗口，需要重新验证
+    fn is_stale(&self) -> bool {
+        self.stored_at.elapsed() >= self.ttl
+    }
+
+    // 还原成一个可以直接返回给调用者的HttpResponse
+    fn to_response(&self) -> HttpResponse {
+        HttpResponse {
+            status: self.status,
+            headers: self.headers.clone(),
+            body: self.body.clone(),
+        }
+    }
+}
+
+// 根据Cache-Control/Expires判断这个响应是否可以缓存，以及新鲜度窗口有多长
+// 注意：本仓库没有引入HTTP日期解析库，无法严格按照Expires的日期计算剩余秒数；
+// 这里只精确解析Cache-Control: max-age=N，遇到只有Expires、没有max-age的响应时退化为一个保守的默认TTL
+fn cache_ttl_from_headers(headers: &HashMap<String, String>) -> Option<Duration> {
+    if let Some(cache_control) = headers.get("Cache-Control") {
+        if cache_control.contains("no-store") || cache_control.contains("no-cache") {
+            return None;
+        }
+        for directive in cache_control.split(',') {
+            if let Some(seconds) = directive.trim().strip_prefix("max-age=") {
+                if let Ok(seconds) = seconds.parse::<u64>() {
+                    return Some(Duration::from_secs(seconds));
+                }
+            }
+        }
+    }
+    if headers.contains_key("Expires") {
+        return Some(Duration::from_secs(60)); // 保守的默认新鲜度窗口，见上面的说明
+    }
+    None
+}
+
+// 缓存存储trait：默认提供内存实现，用户可以换成自己的实现（例如后接磁盘）
+pub trait CacheStore: Send + Sync {
+    fn get(&self, key: &str) -> Option<CacheEntry>;
+    fn put(&self, key: String, entry: CacheEntry);
+}
+
+// 默认的内存缓存实现，按LRU策略淘汰，容量有上限
+pub struct InMemoryCacheStore {
+    max_entries: usize,
+    entries: std::sync::Mutex<HashMap<String, CacheEntry>>,
+    // 最近使用的key在队尾；命中或写入时把对应key挪到队尾，满了就从队首淘汰
+    order: std::sync::Mutex<VecDeque<String>>,
+}
+
+impl InMemoryCacheStore {
+    pub fn new(max_entries: usize) -> Self {
+        InMemoryCacheStore {
+            max_entries,
+            entries: std::sync::Mutex::new(HashMap::new()),
+            order: std::sync::Mutex::new(VecDeque::new()),
+        }
+    }
+
+    fn touch(&self, key: &str) {
+        let mut order = self.order.lock().unwrap();
+        order.retain(|k| k != key);
+        order.push_back(key.to_string());
+    }
+}
+
+impl CacheStore for InMemoryCacheStore {
+    fn get(&self, key: &str) -> Option<CacheEntry> {
+        let entry = self.entries.lock().unwrap().get(key).cloned();
+        if entry.is_some() {
+            self.touch(key);
+        }
+        entry
+    }
+
+    fn put(&self, key: String, entry: CacheEntry) {
+        let mut entries = self.entries.lock().unwrap();
+        if !entries.contains_key(&key) && entries.len() >= self.max_entries {
+            // 缓存已满且这是个新key：淘汰最久未使用的条目腾出空间
+            let oldest = self.order.lock().unwrap().pop_front();
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(key.clone(), entry);
+        drop(entries); // 提前释放锁，避免touch()里再次加锁时产生嵌套锁
+        self.touch(&key);
+    }
+}
+
+// 响应缓存配置：通过HttpClientBuilder::cache(CacheConfig)接入
+#[derive(Clone)]
+pub struct CacheConfig {
+    store: Arc<dyn CacheStore>,
+}
+
+impl CacheConfig {
+    // 默认使用内存实现，最多缓存128条
+    pub fn new() -> Self {
+        CacheConfig { store: Arc::new(InMemoryCacheStore::new(128)) }
+    }
+
+    // 换成自定义的存储实现（例如带磁盘持久化的实现）
+    pub fn with_store(mut self, store: Arc<dyn CacheStore>) -> Self {
+        self.store = store;
+        self
+    }
+
+    // 仍使用内存实现，但调整容量上限
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.store = Arc::new(InMemoryCacheStore::new(max_entries));
+        self
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig::new()
+    }
+}
+
 // ============================================================================
 // HTTP客户端主体实现
 // ============================================================================
@@ -448,13 +745,45 @@ pub struct HttpClient {
     middlewares: Vec<Box<dyn Middleware>>,      // 中间件列表，Box<dyn Trait>是trait对象
     default_timeout: Duration,                   // 默认超时时间
     retry_config: RetryConfig,                  // 重试配置
+    dedupe_inflight: bool,                      // 是否合并并发的相同GET请求
+    // 在途请求表：key是请求指纹，value是该请求完成时用来广播结果的发送端
+    // 后来者订阅同一个发送端，等待领头请求的结果，而不是各自占用一个连接
+    inflight: Arc<Mutex<HashMap<String, broadcast::Sender<Result<HttpResponse>>>>>,
+    cache: Option<CacheConfig>,                 // 未设置时完全不缓存，行为与此前一致
+}
+
+// 退避策略枚举：决定相邻两次重试之间等待多久
+// - Fixed：每次都等待相同的时长（此前唯一支持的行为）
+// - Exponential：等待时长随尝试次数指数增长并叠加抖动，避免大量并发客户端
+//   在服务器恢复的同一瞬间一起重试（即"惊群效应"）
+#[derive(Debug, Clone)]
+pub enum BackoffStrategy {
+    Fixed(Duration),                            // 固定间隔
+    Exponential { base: Duration, max_delay: Duration }, // 指数退避：基础间隔与封顶间隔
+}
+
+impl BackoffStrategy {
+    // 计算第attempt次重试（从0开始计数）应等待的时长
+    // 指数退避公式：min(base * 2^attempt + random_ms, max_delay)
+    // 其中random_ms是每次尝试单独采样的0..=1000毫秒抖动
+    fn delay(&self, attempt: usize) -> Duration {
+        match self {
+            BackoffStrategy::Fixed(delay) => *delay,
+            BackoffStrategy::Exponential { base, max_delay } => {
+                let growth = 1u64.checked_shl(attempt as u32).unwrap_or(u64::MAX);
+                let base_ms = (base.as_millis() as u64).saturating_mul(growth);
+                let jitter_ms = rand::thread_rng().gen_range(0..=1000u64);
+                Duration::from_millis(base_ms.saturating_add(jitter_ms)).min(*max_delay)
+            }
+        }
+    }
 }
 
 // 重试配置结构体
 #[derive(Debug, Clone)]
 pub struct RetryConfig {
     pub max_retries: usize,                     // 最大重试次数
-    pub retry_delay: Duration,                  // 重试间隔时间
+    pub backoff: BackoffStrategy,               // 退避策略，决定每次重试前等待多久
     pub retry_on_status: Vec<u16>,             // 需要重试的HTTP状态码列表
 }
 
@@ -463,7 +792,7 @@ impl Default for RetryConfig {
     fn default() -> Self {
         RetryConfig {
             max_retries: 3,                                    // 默认重试3次
-            retry_delay: Duration::from_millis(1000),         // 默认重试间隔1秒
+            backoff: BackoffStrategy::Fixed(Duration::from_millis(1000)), // 默认固定间隔1秒，与此前行为一致
             retry_on_status: vec![500, 502, 503, 504],        // 服务器错误时重试
         }
     }
@@ -477,9 +806,24 @@ impl HttpClient {
             middlewares: Vec::new(),                 // 空的中间件列表
             default_timeout: Duration::from_secs(30), // 默认30秒超时
             retry_config: RetryConfig::default(),   // 默认重试配置
+            dedupe_inflight: false,                 // 默认不去重，行为与此前一致
+            inflight: Arc::new(Mutex::new(HashMap::new())),
+            cache: None,                             // 默认不开启响应缓存，行为与此前一致
         }
     }
 
+    // 构建器模式：开启/关闭并发请求去重（合并）
+    pub fn with_dedupe_inflight(mut self, enabled: bool) -> Self {
+        self.dedupe_inflight = enabled;
+        self
+    }
+
+    // 构建器模式：开启GET响应缓存
+    pub fn with_cache(mut self, config: CacheConfig) -> Self {
+        self.cache = Some(config);
+        self
+    }
+
     // 构建器模式：设置连接池大小
     pub fn with_pool_size(mut self, size: usize) -> Self {
         self.pool = ConnectionPool::new(size);
@@ -542,6 +886,37 @@ impl HttpClient {
                     HttpResponse::new(500, "Internal Server Error".to_string())
                 } else if request.url.contains("notfound") {
                     HttpResponse::new(404, "Not Found".to_string())
+                } else if request.url.contains("needs-refresh") {
+                    // 只有携带"refreshed-token"的Authorization头才放行，
+                    // 用来演示RefreshAuthMiddleware：第一次用旧token请求收到401，刷新后带着新token重放就能成功
+                    let authorized = request.headers.get("Authorization")
+                        .map(|value| value.contains("refreshed-token"))
+                        .unwrap_or(false);
+                    if authorized {
+                        let mut resp = HttpResponse::new(200, r#"{"message": "token refreshed, request succeeded"}"#.to_string());
+                        resp.headers.insert("Content-Type".to_string(), "application/json".to_string());
+                        resp
+                    } else {
+                        HttpResponse::new(401, "Unauthorized".to_string())
+                    }
+                } else if request.url.contains("/users") {
+                    // 扁平的User JSON，供声明式API绑定层（api_binding!生成的方法）直接反序列化
+                    let mut resp = HttpResponse::new(200, r#"{"id": 1, "name": "test", "email": "test@example.com"}"#.to_string());
+                    resp.headers.insert("Content-Type".to_string(), "application/json".to_string());
+                    resp
+                } else if request.url.contains("cacheable") {
+                    // 携带If-None-Match/If-Modified-Since条件头再次请求时返回304，用来演示缓存重新验证
+                    let revalidating = request.headers.contains_key("If-None-Match")
+                        || request.headers.contains_key("If-Modified-Since");
+                    if revalidating {
+                        HttpResponse::new(304, String::new())
+                    } else {
+                        let mut resp = HttpResponse::new(200, r#"{"message": "freshly fetched", "data": {"id": 1, "name": "test"}}"#.to_string());
+                        resp.headers.insert("Content-Type".to_string(), "application/json".to_string());
+                        resp.headers.insert("Cache-Control".to_string(), "max-age=60".to_string());
+                        resp.headers.insert("ETag".to_string(), "\"v1\"".to_string());
+                        resp
+                    }
                 } else {
                     // r#"..."#是原始字符串字面量，避免转义引号
                     let mut resp = HttpResponse::new(200, r#"{"message": "GET request successful", "data": {"id": 1, "name": "test"}}"#.to_string());
@@ -550,9 +925,16 @@ impl HttpClient {
                 }
             }
             HttpMethod::POST => {
-                let mut resp = HttpResponse::new(201, r#"{"message": "POST request successful", "id": 123}"#.to_string());
-                resp.headers.insert("Content-Type".to_string(), "application/json".to_string());
-                resp
+                if request.url.contains("/users") {
+                    // 同样返回扁平的User JSON，配合api_binding!生成的create_user方法反序列化
+                    let mut resp = HttpResponse::new(201, r#"{"id": 2, "name": "new-user", "email": "new-user@example.com"}"#.to_string());
+                    resp.headers.insert("Content-Type".to_string(), "application/json".to_string());
+                    resp
+                } else {
+                    let mut resp = HttpResponse::new(201, r#"{"message": "POST request successful", "id": 123}"#.to_string());
+                    resp.headers.insert("Content-Type".to_string(), "application/json".to_string());
+                    resp
+                }
             }
             _ => {
                 // 其他HTTP方法的默认响应
@@ -568,7 +950,72 @@ impl HttpClient {
     //   - 不是借用(&HttpRequest)，而是移动(HttpRequest)
     //   - 调用者失去对request的所有权，避免了克隆的开销
     //   - mut关键字允许我们修改request（如添加中间件处理的头部）
-    pub async fn send(&self, mut request: HttpRequest) -> Result<HttpResponse> {
+    pub async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        // 只对幂等的GET请求做在途去重：POST/PUT等有副作用的请求绝不能被合并
+        // dedupe_inflight开关只是总闸，即使打开，非GET请求也会绕过去重直接走下面的发送逻辑
+        if self.dedupe_inflight && matches!(request.method, HttpMethod::GET) {
+            let key = Self::fingerprint(&request);
+
+            // 领头者/跟随者判定：整个判定+插入过程持有锁，避免两个并发请求都误以为自己是领头者
+            enum Role {
+                Leader,
+                Follower(broadcast::Receiver<Result<HttpResponse>>),
+            }
+            let role = {
+                let mut inflight = self.inflight.lock().await;
+                if let Some(tx) = inflight.get(&key) {
+                    Role::Follower(tx.subscribe())
+                } else {
+                    // 容量1：只需要传递“这一次”的结果，不需要历史缓冲
+                    let (tx, _rx) = broadcast::channel(1);
+                    inflight.insert(key.clone(), tx);
+                    Role::Leader
+                }
+            };
+
+            match role {
+                Role::Follower(mut rx) => {
+                    // 跟随者：不占用连接池，只等待领头者广播结果
+                    return rx.recv().await.map_err(|_| {
+                        // 领头者在发送结果前被取消/panic，广播端被drop
+                        // 跟随者不能代替重试（它自己也不是领头者），如实报告错误，调用方可自行重新发起请求
+                        HttpClientError::RequestFailed(
+                            "in-flight leader request was dropped before completing".to_string(),
+                        )
+                    })?;
+                }
+                Role::Leader => {
+                    let result = self.send_uncoalesced(request).await;
+                    // 无论成功失败都要先从表中移除key，失败的领头者不能让后续请求一直卡着等一个已经没人发送的channel
+                    let tx = self.inflight.lock().await.remove(&key);
+                    if let Some(tx) = tx {
+                        // 没有跟随者订阅时send会返回Err，这是正常情况，忽略即可
+                        let _ = tx.send(result.clone());
+                    }
+                    return result;
+                }
+            }
+        }
+
+        self.send_uncoalesced(request).await
+    }
+
+    // 请求指纹：用于识别“同一个”请求，由方法、URL与排序后的头部组成
+    // 头部需要排序，因为HashMap的遍历顺序不固定，否则相同的请求可能生成不同的指纹
+    fn fingerprint(request: &HttpRequest) -> String {
+        let mut headers: Vec<_> = request.headers.iter().collect();
+        headers.sort_by(|a, b| a.0.cmp(b.0));
+        let headers_part = headers
+            .iter()
+            .map(|(k, v)| format!("{}:{}", k, v))
+            .collect::<Vec<_>>()
+            .join("|");
+        format!("{} {} [{}]", request.method, request.url, headers_part)
+    }
+
+    // 实际发送逻辑（中间件 + 重试循环），不做任何在途去重判断
+    // 去重逻辑与发送逻辑分离，避免重试循环内部再去纠结“我是不是领头者”这种状态
+    async fn send_uncoalesced(&self, mut request: HttpRequest) -> Result<HttpResponse> {
         // 设置默认超时时间（如果请求没有指定）
         if request.timeout.is_none() {
             request.timeout = Some(self.default_timeout);
@@ -583,17 +1030,75 @@ impl HttpClient {
             middleware.process_request(&mut request)?;
         }
 
+        // 响应缓存只对GET生效：先查缓存，命中且新鲜就直接返回，完全不碰连接池
+        let cache_key = matches!(request.method, HttpMethod::GET).then(|| request.url.clone());
+        // 如果查到了一个过期的条目，记下来：带上条件请求头重新验证，304时还要用它来复原响应
+        let mut stale_entry: Option<CacheEntry> = None;
+        if let (Some(cache), Some(key)) = (&self.cache, &cache_key) {
+            if let Some(entry) = cache.store.get(key) {
+                if !entry.is_stale() {
+                    return Ok(entry.to_response());
+                }
+                if let Some(etag) = &entry.etag {
+                    request.headers.insert("If-None-Match".to_string(), etag.clone());
+                }
+                if let Some(last_modified) = &entry.last_modified {
+                    request.headers.insert("If-Modified-Since".to_string(), last_modified.clone());
+                }
+                stale_entry = Some(entry);
+            }
+        }
+
+        // 是否已经为这次send()重放过一次请求：只允许重放一次，避免刷新逻辑有误时无限循环
+        let mut replayed = false;
+
         // 重试循环 - 展示Rust的模式匹配和错误处理
         // for attempt in 0..=self.retry_config.max_retries: 范围迭代器
         //   - 0..=n: 包含端点的范围，从0到n（包括n）
         //   - 如果max_retries=3，则尝试0,1,2,3共4次
-        for attempt in 0..=self.retry_config.max_retries {
+        'attempts: for attempt in 0..=self.retry_config.max_retries {
             // 执行请求：&request借用，不转移所有权
             // match表达式：Rust的模式匹配，必须处理所有可能的情况
             match self.execute_request(&request).await {
                 // Ok(mut response): 请求成功，获取响应的可变所有权
                 // mut关键字允许中间件修改响应
                 Ok(mut response) => {
+                    // 先让每个中间件判断是否需要重放（例如401/403时刷新token后重试一次）
+                    // 只要还没用掉本次send()唯一的重放名额，就按第一个要求重放的中间件执行
+                    if !replayed {
+                        for middleware in &self.middlewares {
+                            if let ResponseAction::Replay(replay_request) = middleware.on_response(&request, &response).await? {
+                                request = replay_request;
+                                replayed = true;
+                                continue 'attempts;
+                            }
+                        }
+                    }
+
+                    // 写回/重新验证缓存
+                    if let (Some(cache), Some(key)) = (&self.cache, &cache_key) {
+                        if response.status == 304 {
+                            // 304：服务器说没变化，复用之前的缓存条目，只刷新它的新鲜度窗口
+                            if let Some(mut entry) = stale_entry.take() {
+                                entry.stored_at = Instant::now();
+                                let refreshed = entry.to_response();
+                                cache.store.put(key.clone(), entry);
+                                response = refreshed;
+                            }
+                        } else if let Some(ttl) = cache_ttl_from_headers(&response.headers) {
+                            let entry = CacheEntry {
+                                status: response.status,
+                                headers: response.headers.clone(),
+                                body: response.body.clone(),
+                                stored_at: Instant::now(),
+                                ttl,
+                                etag: response.headers.get("ETag").cloned(),
+                                last_modified: response.headers.get("Last-Modified").cloned(),
+                            };
+                            cache.store.put(key.clone(), entry);
+                        }
+                    }
+
                     // 处理响应中间件：遍历所有中间件
                     // &self.middlewares: 借用中间件列表，不获取所有权
                     for middleware in &self.middlewares {
@@ -601,7 +1106,7 @@ impl HttpClient {
                         // ?操作符：如果中间件处理失败，立即返回错误
                         middleware.process_response(&mut response)?;
                     }
-                    
+
                     // return Ok(response): 成功时立即返回响应
                     // response的所有权被转移给调用者
                     return Ok(response);
@@ -619,10 +1124,10 @@ impl HttpClient {
                     // attempt + 1: 显示人类友好的尝试次数（从1开始）
                     warn!("Request failed on attempt {}, retrying...", attempt + 1);
                     
-                    // 等待重试延迟：tokio::time::sleep异步睡眠
-                    // self.retry_config.retry_delay: 借用重试延迟配置
+                    // 等待退避延迟：tokio::time::sleep异步睡眠
+                    // self.retry_config.backoff.delay(attempt): 根据退避策略计算本次应等待的时长
                     // .await: 等待睡眠完成，让出CPU给其他任务
-                    tokio::time::sleep(self.retry_config.retry_delay).await;
+                    tokio::time::sleep(self.retry_config.backoff.delay(attempt)).await;
                 }
             }
         }
@@ -673,6 +1178,145 @@ impl HttpClient {
         // self.send(request): 转移request的所有权并发送
         self.send(request).await
     }
+
+    // ============================================================================
+    // 流式上传/下载：按块读写磁盘文件，每搬运一块回调一次进度
+    // ============================================================================
+    //
+    // 说明：这个文件里HttpRequest::body/HttpResponse::body到处被当作已经读入内存的
+    // 一整块String使用（json()、中间件、响应缓存子系统等都建立在这个假设上），把它们
+    // 整体换成流式变体会牵动几乎每一处用到body的地方。这里不去动这两个类型，而是新增
+    // 一条独立的流式路径：upload/download直接操作文件路径，不经过HttpRequest::body，
+    // 但仍然复用同一个ConnectionPool（_guard在整个传输期间存活，RAII保证无论成功还是
+    // 中途出错都会释放连接），并以分块+进度回调的方式搬运字节。上传时文件按块读出，
+    // 不会整个读进内存；下载时（模拟的）响应体仍是execute_request返回的一整块String，
+    // 只有写入文件这一步是分块进行的。
+
+    // 流式上传：把path指向的文件内容分块读出发送，每读完一块就回调一次(已发送字节, 总字节数)
+    pub async fn upload<F>(
+        &self,
+        url: &str,
+        path: &std::path::Path,
+        mut progress: F,
+    ) -> Result<HttpResponse>
+    where
+        F: FnMut(u64, Option<u64>),
+    {
+        // 持有连接守卫直到整个文件传输完毕：_guard在函数返回（无论成功还是?提前返回）时自动释放
+        let _guard = self.pool.acquire().await?;
+
+        let file = tokio::fs::File::open(path)
+            .await
+            .map_err(|e| HttpClientError::RequestFailed(format!("failed to open upload file: {e}")))?;
+        let total = file.metadata().await.ok().map(|m| m.len());
+        let mut reader = tokio::io::BufReader::new(file);
+
+        let mut buf = vec![0u8; STREAM_CHUNK_SIZE];
+        let mut sent: u64 = 0;
+        loop {
+            let n = reader
+                .read(&mut buf)
+                .await
+                .map_err(|e| HttpClientError::RequestFailed(format!("failed to read upload file: {e}")))?;
+            if n == 0 {
+                break;
+            }
+            // 模拟把这一块发送到网络上所需的延迟
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            sent += n as u64;
+            progress(sent, total);
+        }
+
+        // 字节已经以流的方式搬运完毕，剩下的请求/响应往来仍走模拟的execute_request
+        let request = HttpRequest::new(HttpMethod::POST, url)?;
+        self.execute_request(&request).await
+    }
+
+    // 流式下载：把(模拟的)响应体分块写入path指向的文件，每写完一块就回调一次(已写入字节, 总字节数)
+    pub async fn download<F>(&self, url: &str, path: &std::path::Path, mut progress: F) -> Result<()>
+    where
+        F: FnMut(u64, Option<u64>),
+    {
+        let _guard = self.pool.acquire().await?;
+
+        let request = HttpRequest::new(HttpMethod::GET, url)?;
+        let response = self.execute_request(&request).await?;
+        let body_bytes = response.body.as_bytes();
+        let total = Some(body_bytes.len() as u64);
+
+        let file = tokio::fs::File::create(path)
+            .await
+            .map_err(|e| HttpClientError::RequestFailed(format!("failed to create download file: {e}")))?;
+        let mut writer = tokio::io::BufWriter::new(file);
+
+        let mut written: u64 = 0;
+        for chunk in body_bytes.chunks(STREAM_CHUNK_SIZE) {
+            writer
+                .write_all(chunk)
+                .await
+                .map_err(|e| HttpClientError::RequestFailed(format!("failed to write download file: {e}")))?;
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            written += chunk.len() as u64;
+            progress(written, total);
+        }
+        writer
+            .flush()
+            .await
+            .map_err(|e| HttpClientError::RequestFailed(format!("failed to flush download file: {e}")))?;
+
+        Ok(())
+    }
+}
+
+// 流式上传/下载每次读写的块大小
+const STREAM_CHUNK_SIZE: usize = 8192;
+
+// ============================================================================
+// 请求取消：通过CancellationToken随时中断一次尚未完成的send
+// ============================================================================
+
+// send_cancellable的返回值：既是一个可以.await的Future，又暴露了一个随时可以从别处调用的abort()
+// 把整个"中间件 -> 重试 -> 退避睡眠"的循环包在一次tokio::select!里，
+// 无论此刻卡在网络调用中还是卡在两次重试之间的sleep里，abort()都能立即让它让路：
+// select!丢弃的那一侧future会被直接drop，其内部持有的ConnectionPool guard也随之按RAII释放
+pub struct CancellableRequest<'a> {
+    token: CancellationToken,
+    response: std::pin::Pin<Box<dyn std::future::Future<Output = Result<HttpResponse>> + Send + 'a>>,
+}
+
+impl<'a> CancellableRequest<'a> {
+    // 取消这次请求。可以在另一个任务里持有这个handle，在原始请求还没完成时随时调用
+    pub fn abort(&self) {
+        self.token.cancel();
+    }
+}
+
+impl<'a> std::future::Future for CancellableRequest<'a> {
+    type Output = Result<HttpResponse>;
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        self.response.as_mut().poll(cx)
+    }
+}
+
+impl HttpClient {
+    // 可取消的发送：返回一个CancellableRequest，调用方可以.await它拿到响应，
+    // 也可以在此之前的任意时刻调用它的abort()方法中断这次请求（包括正在重试退避睡眠的阶段）
+    pub fn send_cancellable(&self, request: HttpRequest) -> CancellableRequest<'_> {
+        let token = CancellationToken::new();
+        let cancel_token = token.clone();
+        let response = Box::pin(async move {
+            tokio::select! {
+                // 被取消：不关心send()内部此刻进行到哪一步，直接返回Cancelled错误
+                _ = cancel_token.cancelled() => Err(HttpClientError::Cancelled),
+                result = self.send(request) => result,
+            }
+        });
+        CancellableRequest { token, response }
+    }
 }
 
 // ============================================================================
@@ -685,6 +1329,8 @@ pub struct HttpClientBuilder {
     timeout: Duration,                          // 超时时间
     retry_config: RetryConfig,                  // 重试配置
     middlewares: Vec<Box<dyn Middleware>>,      // 中间件列表
+    dedupe_inflight: bool,                      // 是否合并并发的相同GET请求
+    cache: Option<CacheConfig>,                 // 是否开启GET响应缓存
 }
 
 impl HttpClientBuilder {
@@ -695,9 +1341,24 @@ impl HttpClientBuilder {
             timeout: Duration::from_secs(30),       // 默认30秒超时
             retry_config: RetryConfig::default(),   // 默认重试配置
             middlewares: Vec::new(),                // 空中间件列表
+            dedupe_inflight: false,                 // 默认不去重，行为与此前一致
+            cache: None,                            // 默认不开启响应缓存，行为与此前一致
         }
     }
 
+    // 构建器模式：开启/关闭并发请求去重（合并）
+    // 仅对GET等幂等请求生效，详见HttpClient::send中的判定
+    pub fn dedupe_inflight(mut self, enabled: bool) -> Self {
+        self.dedupe_inflight = enabled;
+        self
+    }
+
+    // 构建器模式：开启GET响应缓存
+    pub fn cache(mut self, config: CacheConfig) -> Self {
+        self.cache = Some(config);
+        self
+    }
+
     // 构建器模式：设置连接池大小
     pub fn pool_size(mut self, size: usize) -> Self {
         self.pool_size = size;
@@ -715,6 +1376,11 @@ impl HttpClientBuilder {
         self.retry_config = config;
         self
     }
+
+    // 构建器模式：单独设置退避策略，无需重建整个RetryConfig
+    pub fn backoff(mut self, strategy: BackoffStrategy) -> Self {
+        self.retry_config.backoff = strategy;
+        self
     }
 
     // 构建器模式：添加中间件
@@ -731,7 +1397,11 @@ impl HttpClientBuilder {
         let mut client = HttpClient::new()
             .with_pool_size(self.pool_size)      // 设置连接池大小
             .with_timeout(self.timeout)          // 设置超时时间
-            .with_retry_config(self.retry_config); // 设置重试配置
+            .with_retry_config(self.retry_config) // 设置重试配置
+            .with_dedupe_inflight(self.dedupe_inflight); // 设置并发去重开关
+        if let Some(cache) = self.cache {
+            client = client.with_cache(cache); // 设置响应缓存
+        }
 
         // 将构建器中的中间件转移到客户端
         for middleware in self.middlewares {
@@ -740,7 +1410,82 @@ impl HttpClientBuilder {
 
         client // 返回配置完成的客户端
     }
+}
+
+// ============================================================================
+// 声明式类型化API绑定层：仿Retrofit风格，在编译期把URL拼接/序列化/发送/反序列化的样板代码生成出来
+// ============================================================================
+
+// 说明：典型的Retrofit风格实现会用属性宏标注在trait方法上，例如#[get("/users/{id}")]，
+// 那需要一个单独的proc-macro crate（Cargo.toml里标注proc-macro = true，像summary_derive那样）。
+// 这里没有为API绑定单独建这样一个crate，而是改用声明式宏（macro_rules!）达到同样的目的：调用者写一段描述接口的DSL，
+// 宏在编译期展开成trait定义、一个持有HttpClient的实现结构体，以及每个方法真正的
+// 请求构建+发送+反序列化逻辑，仍然是“声明一次、样板代码由编译器生成”。
+// 依赖原生的trait异步方法（自Rust 1.75起稳定），不需要额外引入async_trait。
+macro_rules! api_binding {
+    (
+        trait $trait_name:ident {
+            get {
+                $(fn $gfn:ident($($garg:ident : $gty:ty),*) -> $gret:ty => $gpath:literal;)*
+            }
+            post {
+                $(fn $pfn:ident(body : $pbody_ty:ty $(, $parg:ident : $pty:ty)*) -> $pret:ty => $ppath:literal;)*
+            }
+        }
+        client $client_name:ident;
+    ) => {
+        // 生成的trait：面向接口编程，测试时可以换成mock实现，而不必真的发网络请求
+        trait $trait_name {
+            $(
+                async fn $gfn(&self, $($garg: $gty),*) -> Result<$gret>;
+            )*
+            $(
+                async fn $pfn(&self, body: $pbody_ty, $($parg: $pty),*) -> Result<$pret>;
+            )*
+        }
 
+        // 生成的客户端：包装一个HttpClient和base_url，是trait的唯一“真实”实现
+        struct $client_name {
+            http: HttpClient,
+            base_url: String,
+        }
+
+        impl $client_name {
+            fn new(http: HttpClient, base_url: impl Into<String>) -> Self {
+                $client_name { http, base_url: base_url.into() }
+            }
+        }
+
+        impl $trait_name for $client_name {
+            $(
+                // 生成的GET方法：把{占位符}替换成参数值，拼出完整URL，发送后反序列化为返回类型
+                async fn $gfn(&self, $($garg: $gty),*) -> Result<$gret> {
+                    let mut path = $gpath.to_string();
+                    $(
+                        path = path.replace(concat!("{", stringify!($garg), "}"), &$garg.to_string());
+                    )*
+                    let url = format!("{}{}", self.base_url, path);
+                    let request = HttpRequest::new(HttpMethod::GET, &url)?;
+                    let response = self.http.send(request).await?;
+                    response.json::<$gret>()
+                }
+            )*
+            $(
+                // 生成的POST方法：同样替换路径占位符，另外把body序列化为JSON请求体
+                async fn $pfn(&self, body: $pbody_ty, $($parg: $pty),*) -> Result<$pret> {
+                    let mut path = $ppath.to_string();
+                    $(
+                        path = path.replace(concat!("{", stringify!($parg), "}"), &$parg.to_string());
+                    )*
+                    let url = format!("{}{}", self.base_url, path);
+                    let request = HttpRequest::new(HttpMethod::POST, &url)?.json(&body)?;
+                    let response = self.http.send(request).await?;
+                    response.json::<$pret>()
+                }
+            )*
+        }
+    };
+}
 
 // ============================================================================
 // 示例数据结构：用于演示JSON序列化和反序列化
@@ -763,6 +1508,21 @@ struct ApiResponse<T> {
     data: Option<T>,    // 响应数据，Option表示可能为空
 }
 
+// 调用api_binding!宏，声明UserApi接口及其生成的客户端UserApiClient
+// 展开后会得到：trait UserApi、struct UserApiClient，以及UserApiClient对UserApi的实现
+api_binding! {
+    trait UserApi {
+        get {
+            fn get_user(id: u32) -> User => "/users/{id}";
+            fn list_users() -> Vec<User> => "/users";
+        }
+        post {
+            fn create_user(body: User) -> User => "/users";
+        }
+    }
+    client UserApiClient;
+}
+
 // ============================================================================
 // 主函数：演示HTTP客户端的使用
 // ============================================================================
@@ -785,7 +1545,10 @@ async fn main() -> Result<()> {
         .timeout(Duration::from_secs(10))       // 设置超时时间为10秒
         .retry_config(RetryConfig {             // 自定义重试配置
             max_retries: 2,                     // 最大重试2次
-            retry_delay: Duration::from_millis(500), // 重试间隔500毫秒
+            backoff: BackoffStrategy::Exponential {
+                base: Duration::from_millis(200),
+                max_delay: Duration::from_secs(5),
+            }, // 指数退避 + 抖动
             retry_on_status: vec![500, 502, 503], // 在这些状态码时重试
         })
         .add_middleware(LoggingMiddleware::new()) // 添加日志中间件
@@ -870,7 +1633,125 @@ async fn main() -> Result<()> {
     }
 
     // ============================================================================
-    // 5. 连接池状态检查
+    // 5. 并发请求去重演示
+    // ============================================================================
+    println!("\n🧩 测试并发请求去重...");
+    // 单独构建一个开启了dedupe_inflight的客户端：三个并发的相同GET请求应当合并为一次真实发送
+    let dedupe_client = HttpClientBuilder::new()
+        .dedupe_inflight(true)
+        .build();
+    // tokio::join!并发运行三个future，三者的方法+URL+头部完全一致，理应共享同一次发送
+    let (r1, r2, r3) = tokio::join!(
+        dedupe_client.get("https://api.example.com/shared"),
+        dedupe_client.get("https://api.example.com/shared"),
+        dedupe_client.get("https://api.example.com/shared"),
+    );
+    for (i, result) in [r1, r2, r3].into_iter().enumerate() {
+        match result {
+            Ok(response) => println!("  请求{}: Status {}", i + 1, response.status),
+            Err(e) => println!("  请求{}失败: {:?}", i + 1, e),
+        }
+    }
+
+    // ============================================================================
+    // 6. 声明式类型化API绑定层演示
+    // ============================================================================
+    println!("\n🧬 测试类型化API绑定（api_binding!生成）...");
+    // UserApiClient和UserApi trait都是api_binding!宏展开出来的，这里只是使用它们
+    let user_api = UserApiClient::new(HttpClientBuilder::new().build(), "https://api.example.com");
+    match user_api.get_user(1).await {
+        Ok(user) => println!("✅ get_user(1) => {:?}", user),
+        Err(e) => println!("❌ get_user(1)失败: {:?}", e),
+    }
+    let new_user = User {
+        id: 0,
+        name: "李四".to_string(),
+        email: "lisi@example.com".to_string(),
+    };
+    match user_api.create_user(new_user).await {
+        Ok(user) => println!("✅ create_user(..) => {:?}", user),
+        Err(e) => println!("❌ create_user(..)失败: {:?}", e),
+    }
+
+    // ============================================================================
+    // 7. Token刷新中间件与状态码拦截演示
+    // ============================================================================
+    println!("\n🔐 测试Token刷新中间件...");
+    let refresh_client = HttpClientBuilder::new()
+        .add_middleware(RefreshAuthMiddleware::new(
+            "expired-token".to_string(),
+            || -> BoxFuture<'static, Result<String>> {
+                Box::pin(async { Ok("refreshed-token".to_string()) })
+            },
+        ))
+        .add_middleware(StatusCodeMiddleware::new().on_status(403, |_| HttpClientError::AuthExpired))
+        .build();
+    // 第一次请求会带着旧token收到401，中间件刷新token后自动重放一次
+    match refresh_client.get("https://api.example.com/needs-refresh").await {
+        Ok(response) => println!("✅ 刷新后请求成功: Status {}, Body {}", response.status, response.body),
+        Err(e) => println!("❌ 刷新后请求仍失败: {:?}", e),
+    }
+
+    // ============================================================================
+    // 8. 响应缓存演示
+    // ============================================================================
+    println!("\n🗄️ 测试GET响应缓存...");
+    let cache_client = HttpClientBuilder::new()
+        .cache(CacheConfig::new())
+        .build();
+    // 第一次请求：缓存未命中，真正打到（模拟的）网络，响应带Cache-Control: max-age=60被存入缓存
+    match cache_client.get("https://api.example.com/cacheable").await {
+        Ok(response) => println!("  第一次请求: Status {}, Body {}", response.status, response.body),
+        Err(e) => println!("  第一次请求失败: {:?}", e),
+    }
+    // 第二次请求：缓存仍新鲜，直接从CacheStore返回，不再经过execute_request
+    match cache_client.get("https://api.example.com/cacheable").await {
+        Ok(response) => println!("  第二次请求(应命中缓存): Status {}, Body {}", response.status, response.body),
+        Err(e) => println!("  第二次请求失败: {:?}", e),
+    }
+
+    // ============================================================================
+    // 9. 流式上传/下载演示
+    // ============================================================================
+    println!("\n📦 测试流式上传/下载...");
+    let stream_client = HttpClientBuilder::new().build();
+    let upload_path = std::env::temp_dir().join("http_client_demo_upload.txt");
+    tokio::fs::write(&upload_path, "x".repeat(20_000)).await.ok();
+    match stream_client
+        .upload("https://api.example.com/upload", &upload_path, |sent, total| {
+            println!("  上传进度: {} / {:?} 字节", sent, total);
+        })
+        .await
+    {
+        Ok(response) => println!("✅ 上传完成: Status {}", response.status),
+        Err(e) => println!("❌ 上传失败: {:?}", e),
+    }
+    let download_path = std::env::temp_dir().join("http_client_demo_download.json");
+    match stream_client
+        .download("https://api.example.com/users", &download_path, |written, total| {
+            println!("  下载进度: {} / {:?} 字节", written, total);
+        })
+        .await
+    {
+        Ok(()) => println!("✅ 下载完成，已写入 {}", download_path.display()),
+        Err(e) => println!("❌ 下载失败: {:?}", e),
+    }
+
+    // ============================================================================
+    // 10. 请求取消演示
+    // ============================================================================
+    println!("\n🛑 测试请求取消...");
+    let cancel_client = HttpClientBuilder::new().build();
+    let handle = cancel_client.send_cancellable(HttpRequest::new(HttpMethod::GET, "https://api.example.com/slow")?);
+    // 还没等请求完成就调用abort()：无论它此刻是在"网络中"还是在两次重试间的退避睡眠里都会被打断
+    handle.abort();
+    match handle.await {
+        Ok(response) => println!("✅ 请求意外完成: Status {}", response.status),
+        Err(e) => println!("🛑 请求已取消: {:?}", e),
+    }
+
+    // ============================================================================
+    // 11. 连接池状态检查
     // ============================================================================
     println!("\n🏊 连接池状态:");
     // 显示当前可用的连接数
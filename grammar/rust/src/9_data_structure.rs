@@ -29,61 +29,240 @@ impl<T> Queue<T> {
 
 }
 
+use rand::Rng;
+
+// 跳表节点在arena（Vec<Node<T>>）里按插入顺序存放，forward存的是指向
+// 别的节点在arena里下标的"指针"，NIL(usize::MAX)代表空指针——
+// 这样可以在安全Rust里模拟链式结构，不需要Box/Rc或unsafe。
+const NIL: usize = usize::MAX;
+const MAX_LEVEL: usize = 16;
+
+struct Node<T> {
+    value: T,
+    forward: Vec<usize>,
+}
+
 struct SkipList<T: Ord> {
-    data: Vec<T>,
+    nodes: Vec<Node<T>>,
+    // 头节点没有value，只有各层的forward指针，单独存放
+    head: Vec<usize>,
+    // 当前跳表实际用到的最高层级（0-indexed）
+    top_level: usize,
 }
 
 impl<T: Ord> SkipList<T> {
     pub fn new() -> Self {
-        Self { data: Vec::new() }
+        Self {
+            nodes: Vec::new(),
+            head: vec![NIL],
+            top_level: 0,
+        }
     }
 
-    pub fn insert(&mut self, item: T) {
-        self.data.push(item);
+    // 抛硬币决定新节点的层数：每次有1/2的概率再往上长一层，封顶MAX_LEVEL-1
+    fn random_level() -> usize {
+        let mut level = 0;
+        let mut rng = rand::thread_rng();
+        while level < MAX_LEVEL - 1 && rng.gen::<bool>() {
+            level += 1;
+        }
+        level
+    }
+
+    // `node`用NIL代表头节点，否则是arena下标；统一走这个helper读某一层的forward指针
+    fn forward_at(&self, node: usize, level: usize) -> usize {
+        if node == NIL {
+            *self.head.get(level).unwrap_or(&NIL)
+        } else {
+            *self.nodes[node].forward.get(level).unwrap_or(&NIL)
+        }
+    }
+
+    // 写某一层的forward指针；当某个节点第一次在这一层出现时按需把它的forward数组长高
+    fn set_forward_at(&mut self, node: usize, level: usize, target: usize) {
+        if node == NIL {
+            if level >= self.head.len() {
+                self.head.resize(level + 1, NIL);
+            }
+            self.head[level] = target;
+        } else {
+            let forward = &mut self.nodes[node].forward;
+            if level >= forward.len() {
+                forward.resize(level + 1, NIL);
+            }
+            forward[level] = target;
+        }
     }
-    
+
+    pub fn insert(&mut self, value: T) {
+        let mut update = vec![NIL; MAX_LEVEL];
+        let mut x = NIL;
+        for level in (0..=self.top_level).rev() {
+            let mut curr = self.forward_at(x, level);
+            while curr != NIL && self.nodes[curr].value < value {
+                x = curr;
+                curr = self.forward_at(x, level);
+            }
+            update[level] = x;
+        }
+
+        let new_level = Self::random_level();
+        if new_level > self.top_level {
+            self.top_level = new_level;
+        }
+
+        let new_index = self.nodes.len();
+        self.nodes.push(Node {
+            value,
+            forward: vec![NIL; new_level + 1],
+        });
+
+        for level in 0..=new_level {
+            let pred = update[level];
+            let succ = self.forward_at(pred, level);
+            self.set_forward_at(new_index, level, succ);
+            self.set_forward_at(pred, level, new_index);
+        }
+    }
+
     pub fn search(&self, item: &T) -> bool {
-        self.data.contains(item)
+        let mut x = NIL;
+        for level in (0..=self.top_level).rev() {
+            let mut curr = self.forward_at(x, level);
+            while curr != NIL && &self.nodes[curr].value < item {
+                x = curr;
+                curr = self.forward_at(x, level);
+            }
+        }
+        let candidate = self.forward_at(x, 0);
+        candidate != NIL && &self.nodes[candidate].value == item
     }
 
+    pub fn remove(&mut self, item: &T) -> bool {
+        let mut update = vec![NIL; self.top_level + 1];
+        let mut x = NIL;
+        for level in (0..=self.top_level).rev() {
+            let mut curr = self.forward_at(x, level);
+            while curr != NIL && &self.nodes[curr].value < item {
+                x = curr;
+                curr = self.forward_at(x, level);
+            }
+            update[level] = x;
+        }
+
+        let victim = self.forward_at(x, 0);
+        if victim == NIL || &self.nodes[victim].value != item {
+            return false;
+        }
+
+        let victim_top = self.nodes[victim].forward.len() - 1;
+        for level in 0..=victim_top {
+            let succ = self.forward_at(victim, level);
+            self.set_forward_at(update[level], level, succ);
+        }
 
+        while self.top_level > 0 && self.forward_at(NIL, self.top_level) == NIL {
+            self.top_level -= 1;
+        }
+
+        // 注意：这里只是把victim从各层forward链里摘掉，它在`nodes`这个arena里的
+        // 槽位并不会被回收或复用——要做到这一点需要一个空闲槽位链表，
+        // 这里为了保持安全Rust、不引入unsafe而省略了这部分优化。
+        true
+    }
 }
 use std::hash::{Hash, Hasher};
+
+// 布隆过滤器：用Vec<u64>按位压缩存储（而不是每个bit占一个bool的Vec<bool>，
+// 省8倍内存），每个元素用双重哈希（double hashing）派生k个探测位，
+// g_i = h1 + i*h2 (mod m)，避免真的要跑k次独立哈希函数。
 struct BloomFilter {
-    
-    data: Vec<bool>,
-    size: usize,
+    bits: Vec<u64>,
+    // 位数组总位数（m）
+    num_bits: usize,
+    // 每个元素需要置位的哈希个数（k）
+    num_hashes: usize,
 }
 
 impl BloomFilter {
     pub fn new() -> Self {
-        Self { data: Vec::new(), size: 1000 }
+        Self::with_params(1000, 0.01)
     }
-    pub fn hash(&self, item: &impl Hash) -> usize {
-        let mut hasher = std::collections::hash_map::DefaultHasher::new();
-        item.hash(&mut hasher);
-        hasher.finish() as usize
+
+    /// 按预期元素数`expected_items`和目标假阳性率`fp_rate`计算最优的位数m与哈希个数k：
+    /// m = ceil(-n*ln(p) / (ln2)^2)，k = round((m/n)*ln2)
+    pub fn with_params(expected_items: usize, fp_rate: f64) -> Self {
+        let n = (expected_items.max(1)) as f64;
+        let ln2 = std::f64::consts::LN_2;
+
+        let m = (-n * fp_rate.ln() / (ln2 * ln2)).ceil() as usize;
+        let num_bits = m.max(1);
+
+        let k = ((num_bits as f64 / n) * ln2).round() as usize;
+        let num_hashes = k.max(1);
+
+        let words = (num_bits + 63) / 64;
+        Self {
+            bits: vec![0u64; words],
+            num_bits,
+            num_hashes,
+        }
     }
-    
-    pub fn insert(&mut self, item: &impl Hash) {
-        let index = self.hash(item) % self.size;
-        self.data[index] = true;
+
+    /// 从同一个item派生两个独立的64位基础哈希h1、h2，用于后续双重哈希。
+    fn hash_pair(&self, item: &impl Hash) -> (u64, u64) {
+        let mut hasher1 = std::collections::hash_map::DefaultHasher::new();
+        item.hash(&mut hasher1);
+        let h1 = hasher1.finish();
+
+        let mut hasher2 = std::collections::hash_map::DefaultHasher::new();
+        h1.hash(&mut hasher2);
+        item.hash(&mut hasher2);
+        let h2 = hasher2.finish();
+
+        (h1, h2)
     }
-    
-    pub fn search(&self, item: &impl Hash) -> bool {
-        let index = self.hash(item) % self.size;
-        self.data[index]
+
+    fn probe_index(&self, h1: u64, h2: u64, i: u64) -> usize {
+        let g = h1.wrapping_add(i.wrapping_mul(h2));
+        (g % self.num_bits as u64) as usize
     }
-    pub fn init(&mut self, size: usize) {
-        self.data = vec![false; size];
+
+    fn set_bit(&mut self, index: usize) {
+        self.bits[index / 64] |= 1u64 << (index % 64);
+    }
+
+    fn get_bit(&self, index: usize) -> bool {
+        (self.bits[index / 64] & (1u64 << (index % 64))) != 0
+    }
+
+    pub fn insert(&mut self, item: &impl Hash) {
+        let (h1, h2) = self.hash_pair(item);
+        for i in 0..self.num_hashes as u64 {
+            let index = self.probe_index(h1, h2, i);
+            self.set_bit(index);
+        }
     }
 
+    /// 只有k个探测位全部被置位才认为"可能存在"；只要有一位是0就一定不存在。
+    pub fn search(&self, item: &impl Hash) -> bool {
+        let (h1, h2) = self.hash_pair(item);
+        (0..self.num_hashes as u64).all(|i| self.get_bit(self.probe_index(h1, h2, i)))
+    }
 }
 
 fn main() {
-    let mut bloom_filter = BloomFilter::new();
-    bloom_filter.init(1000);
+    let mut bloom_filter = BloomFilter::with_params(1000, 0.01);
     bloom_filter.insert(&"hello");
     println!("{}", bloom_filter.search(&"hello"));
     println!("{}", bloom_filter.search(&"world"));
+
+    let mut skip_list = SkipList::new();
+    for item in [5, 1, 9, 3, 7] {
+        skip_list.insert(item);
+    }
+    println!("{}", skip_list.search(&7));
+    println!("{}", skip_list.search(&4));
+    skip_list.remove(&7);
+    println!("{}", skip_list.search(&7));
 }
\ No newline at end of file
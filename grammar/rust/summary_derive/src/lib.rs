@@ -0,0 +1,60 @@
+// summary_derive: 为Summary trait提供#[derive(Summary)]
+//
+// 6_advanced_features.rs里NewsArticle/Tweet的Summary是手写的，各自拼接自己的字段；
+// 这个过程宏则为具名字段结构体生成一套固定模板："<类型名>: (read more from <第一个String字段>)"，
+// 让调用方只需要#[derive(Summary)]，不用再手写一遍。
+// 过程宏必须放在proc-macro = true的独立crate里，这是syn/quote/proc-macro2这套工具链
+// 能工作的前提，也是这个crate单独存在（而不是和调用方写在同一个文件里）的原因。
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Type};
+
+#[proc_macro_derive(Summary)]
+pub fn derive_summary(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+
+    // 找结构体里第一个类型字面上是String的具名字段
+    let first_string_field = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields
+                .named
+                .iter()
+                .find(|field| is_string_type(&field.ty))
+                .and_then(|field| field.ident.as_ref()),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    let body = match first_string_field {
+        Some(field) => quote! {
+            format!("{}: (read more from {})", stringify!(#name), self.#field)
+        },
+        None => quote! {
+            format!("{}: (read more...)", stringify!(#name))
+        },
+    };
+
+    let expanded = quote! {
+        impl Summary for #name {
+            fn summarize(&self) -> String {
+                #body
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+// 只做最朴素的路径匹配：字段类型是否就是（不带任何前缀的）String
+fn is_string_type(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.is_ident("String"))
+}
+
+// 测试见 tests/derive_summary.rs：proc-macro crate不能在自己的lib.rs里用
+// #[cfg(test)]直接调用自己导出的派生宏——#[derive(Summary)]要求Summary宏
+// 出现在"依赖方"的编译单元里，而单元测试是和lib.rs本身一起编译的，此时
+// summary_derive还不是自己的依赖。放到tests/下的集成测试、把summary_derive
+// 加进[dev-dependencies]指向自身，就能让测试像真正的调用方一样使用这个宏。
@@ -0,0 +1,38 @@
+// 集成测试：在这里summary_derive是"外部"依赖，#[derive(Summary)]才能像
+// 6_advanced_features.rs里那样真正被展开和调用。
+
+use summary_derive::Summary;
+
+trait Summary {
+    fn summarize(&self) -> String;
+}
+
+// `body`/`count`从未被直接读取——它们只是用来验证派生宏"挑出第一个String
+// 字段、其余字段不影响结果"这件事，所以允许它们保持未读。
+#[derive(Summary)]
+struct BlogPost {
+    title: String,
+    #[allow(dead_code)]
+    body: String,
+}
+
+#[derive(Summary)]
+struct Counter {
+    #[allow(dead_code)]
+    count: u32,
+}
+
+#[test]
+fn derives_from_first_string_field() {
+    let post = BlogPost {
+        title: "Hello".to_string(),
+        body: "World".to_string(),
+    };
+    assert_eq!(post.summarize(), "BlogPost: (read more from Hello)");
+}
+
+#[test]
+fn falls_back_when_no_string_field() {
+    let counter = Counter { count: 5 };
+    assert_eq!(counter.summarize(), "Counter: (read more...)");
+}
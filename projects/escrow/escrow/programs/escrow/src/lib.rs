@@ -0,0 +1,190 @@
+#![allow(unexpected_cfgs)]
+#![allow(deprecated)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, System};
+
+// 本程序演示一个预付费托管（escrow）支付流程：
+// 1) 每个用户有一个自己的托管 PDA（seeds=[b"ESCROW", user.key()]），存入SOL后才能消费；
+// 2) 所有用户实际消费的金额最终都汇入同一个程序持有的储备金 PDA（seeds=[b"RESERVE"]）。
+//
+// 托管 PDA 和储备金 PDA 都是本程序拥有的账户（owner=本程序，而非 System Program），
+// 所以“转出”lamports时不能走`system_program::transfer`CPI——那条路径要求`from`账户归
+// System Program所有。本程序改用`try_borrow_mut_lamports`直接对两个账户做lamports算术
+// 搬运，这是在同一笔交易里从程序自持PDA向另一个账户转移lamports的标准写法。
+
+declare_id!("6sM1nF2wC5qJYbqfxPvw3dYV3hZ1oNn8oJZ1bWktbNSq");
+
+#[program]
+pub mod escrow {
+    use super::*;
+
+    /// 为signer开一个预付费托管账户（PDA），记录owner与当前余额（初始为0）。
+    pub fn init_escrow(ctx: Context<InitEscrow>) -> Result<()> {
+        ctx.accounts.escrow.owner = ctx.accounts.user.key();
+        ctx.accounts.escrow.balance = 0;
+        Ok(())
+    }
+
+    /// 把signer的SOL存入自己的托管账户。
+    ///
+    /// signer自己的账户是系统程序拥有的，满足`system_program::transfer`对`from`的要求，
+    /// 因此存款走普通的System Program CPI即可，不需要PDA签名。
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.user.to_account_info(),
+            to: ctx.accounts.escrow.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?;
+
+        ctx.accounts.escrow.balance = ctx
+            .accounts
+            .escrow
+            .balance
+            .checked_add(amount)
+            .ok_or(EscrowErrorCode::AmountOverflow)?;
+
+        Ok(())
+    }
+
+    /// 把托管账户里的全部余额还给owner。
+    pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
+        let amount = ctx.accounts.escrow.balance;
+        require!(amount > 0, EscrowErrorCode::NothingToWithdraw);
+
+        **ctx.accounts.escrow.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.owner.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        ctx.accounts.escrow.balance = 0;
+
+        Ok(())
+    }
+
+    /// 从托管余额里扣款并计入储备金账户，用于结算一次实际发生的消费。
+    pub fn pay(ctx: Context<Pay>, amount: u64) -> Result<()> {
+        require!(
+            ctx.accounts.escrow.balance >= amount,
+            EscrowErrorCode::InsufficientBalance
+        );
+
+        **ctx.accounts.escrow.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.reserve.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        ctx.accounts.escrow.balance -= amount;
+        ctx.accounts.reserve.total_collected = ctx
+            .accounts
+            .reserve
+            .total_collected
+            .checked_add(amount)
+            .ok_or(EscrowErrorCode::AmountOverflow)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitEscrow<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + Escrow::INIT_SPACE,
+        seeds = [b"ESCROW", user.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"ESCROW", user.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    /// 托管账户的所有者，也是取款的收款方
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"ESCROW", owner.key().as_ref()],
+        bump,
+        constraint = escrow.owner == owner.key() @ EscrowErrorCode::Unauthorized
+    )]
+    pub escrow: Account<'info, Escrow>,
+}
+
+#[derive(Accounts)]
+pub struct Pay<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"ESCROW", signer.key().as_ref()],
+        bump,
+        constraint = escrow.owner == signer.key() @ EscrowErrorCode::Unauthorized
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + Reserve::INIT_SPACE,
+        seeds = [b"RESERVE"],
+        bump
+    )]
+    pub reserve: Account<'info, Reserve>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 每个用户的预付费托管账户。
+#[account]
+#[derive(InitSpace)]
+pub struct Escrow {
+    /// 允许存取款、发起支付的所有者
+    pub owner: Pubkey,
+
+    /// 当前可用余额（单位：lamports）
+    pub balance: u64,
+}
+
+/// 全局唯一的储备金账户，汇总所有`pay`指令实际结算的金额。
+#[account]
+#[derive(InitSpace)]
+pub struct Reserve {
+    /// 历史累计结算金额（单位：lamports）
+    pub total_collected: u64,
+}
+
+#[error_code]
+pub enum EscrowErrorCode {
+    #[msg("Signer is not the owner of this escrow account")]
+    Unauthorized,
+
+    #[msg("Escrow balance is insufficient for this payment")]
+    InsufficientBalance,
+
+    #[msg("Escrow has nothing to withdraw")]
+    NothingToWithdraw,
+
+    #[msg("Amount overflows the account's counter")]
+    AmountOverflow,
+}
@@ -87,6 +87,98 @@ pub mod chain {
         });
         Ok(())
     }
+
+    /// 把钱包 PDA 里的 SOL 取回给 `payer`（即当初创建这个钱包的出资者）。
+    ///
+    /// 钱包账户本身没有私钥，只能由本程序通过 `seeds + bump` 以 PDA 签名的方式
+    /// 对系统程序发起转账 CPI——这与 `create_wallet` 里创建账户用的是同一套签名种子。
+    pub fn withdraw_wallet(ctx: Context<WithdrawWallet>, _seed: String, lamports: u64) -> Result<()> {
+        guard_rent_exemption_after_withdrawal(&ctx.accounts.wallet.to_account_info(), lamports)?;
+
+        let wallet_seeds: &[&[u8]] = &[
+            b"wallet",
+            ctx.accounts.payer.key.as_ref(),
+            &[ctx.bumps.wallet],
+        ];
+        let signer_seeds: &[&[&[u8]]] = &[wallet_seeds];
+
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.wallet.to_account_info(),
+            to: ctx.accounts.payer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        system_program::transfer(cpi_ctx, lamports)?;
+
+        emit!(TransferEvent {
+            from: ctx.accounts.wallet.key(),
+            to: ctx.accounts.payer.key(),
+            lamports,
+        });
+
+        Ok(())
+    }
+
+    /// 把钱包 PDA 里的 SOL 转给任意目标账户 `to`。
+    ///
+    /// 与 `withdraw_wallet` 的唯一区别是转账目标不固定为 `payer`，
+    /// 而是调用方指定的任意系统账户（由 `to` 账户上的 `address = to_pubkey` 约束校验其地址确实等于参数 `to_pubkey`）。
+    pub fn transfer_wallet(
+        ctx: Context<TransferWallet>,
+        _seed: String,
+        to_pubkey: Pubkey,
+        lamports: u64,
+    ) -> Result<()> {
+        guard_rent_exemption_after_withdrawal(&ctx.accounts.wallet.to_account_info(), lamports)?;
+
+        let wallet_seeds: &[&[u8]] = &[
+            b"wallet",
+            ctx.accounts.payer.key.as_ref(),
+            &[ctx.bumps.wallet],
+        ];
+        let signer_seeds: &[&[&[u8]]] = &[wallet_seeds];
+
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.wallet.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        system_program::transfer(cpi_ctx, lamports)?;
+
+        emit!(TransferEvent {
+            from: ctx.accounts.wallet.key(),
+            to: to_pubkey,
+            lamports,
+        });
+
+        Ok(())
+    }
+}
+
+/// 转账前置检查：扣款后钱包要么被掏空到 0 lamports（账户本身数据为空，
+/// 清零后不再需要维持免租金状态），要么仍然不低于免租金最低余额，
+/// 不允许出现"既不是0、又低于免租金线"的中间状态。
+fn guard_rent_exemption_after_withdrawal(
+    wallet: &AccountInfo,
+    lamports: u64,
+) -> Result<()> {
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(wallet.data_len());
+    let remaining = wallet
+        .lamports()
+        .checked_sub(lamports)
+        .ok_or(ChainErrorCode::InsufficientBalance)?;
+    require!(
+        remaining == 0 || remaining >= rent_exempt_minimum,
+        ChainErrorCode::WithdrawalBreaksRentExemption
+    );
+    Ok(())
 }
 
 /// 创建系统钱包账户时使用的账户集合。
@@ -105,6 +197,7 @@ pub struct CreateWallet<'info> {
     /// - 使用 `seeds`+`bump` 派生地址；
     /// - 这里改为使用 `UncheckedAccount` 并在指令中手动 CPI 创建，
     ///   以规避某些环境下 `#[derive(Accounts)]` 的宏展开问题。
+    ///
     /// CHECK: 该账户地址由本程序使用 `seeds = [b"wallet", payer.key()]` 与 `bump`
     /// 派生（PDA），并在指令中通过对系统程序的 CPI 创建为系统账户（owner=System,
     /// space=0）。我们仅读取其 `lamports`，不依赖任何自定义数据结构，因此无需
@@ -130,6 +223,50 @@ pub struct GetBalance<'info> {
     pub wallet: SystemAccount<'info>,
 }
 
+/// 取款时使用的账户集合：把钱包 PDA 里的 SOL 转回给当初创建它的 `payer`。
+#[derive(Accounts)]
+#[instruction(seed: String)]
+pub struct WithdrawWallet<'info> {
+    /// 当初创建这个钱包的出资者，这里也是取款的收款方
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: 同 `CreateWallet`——地址由 `seeds = [b"wallet", payer.key()]` 与
+    /// `bump` 派生，本程序只通过 PDA 签名对它做 lamports 转账，不依赖自定义数据结构。
+    #[account(
+        mut,
+        seeds = [b"wallet", payer.key().as_ref()],
+        bump
+    )]
+    pub wallet: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 转账时使用的账户集合：把钱包 PDA 里的 SOL 转给调用方指定的任意系统账户 `to`。
+#[derive(Accounts)]
+#[instruction(seed: String, to_pubkey: Pubkey)]
+pub struct TransferWallet<'info> {
+    /// 钱包 PDA 的出资者，同时也是派生 `wallet` 地址所需的签名者
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: 同 `CreateWallet`
+    #[account(
+        mut,
+        seeds = [b"wallet", payer.key().as_ref()],
+        bump
+    )]
+    pub wallet: UncheckedAccount<'info>,
+
+    /// CHECK: 转账目标，只接收 lamports，不需要校验其数据结构；
+    /// `address = to_pubkey` 约束保证它确实是调用方通过指令参数传入的那个账户。
+    #[account(mut, address = to_pubkey)]
+    pub to: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
 /// 用于在链上事件中输出余额信息，方便客户端解析。
 #[event]
 pub struct BalanceEvent {
@@ -138,3 +275,23 @@ pub struct BalanceEvent {
     /// 余额（单位：lamports；1 SOL = 1_000_000_000 lamports）
     pub lamports: u64,
 }
+
+/// 用于在链上事件中输出一次取款/转账操作，方便客户端解析。
+#[event]
+pub struct TransferEvent {
+    /// 转出方（钱包 PDA）
+    pub from: Pubkey,
+    /// 收款方
+    pub to: Pubkey,
+    /// 转账金额（单位：lamports）
+    pub lamports: u64,
+}
+
+#[error_code]
+pub enum ChainErrorCode {
+    #[msg("Wallet does not have enough lamports for this withdrawal")]
+    InsufficientBalance,
+
+    #[msg("Withdrawal would leave the wallet below the rent-exempt minimum")]
+    WithdrawalBreaksRentExemption,
+}
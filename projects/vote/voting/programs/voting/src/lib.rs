@@ -1,7 +1,7 @@
 #![allow(unexpected_cfgs)]
 #![allow(deprecated)]
 
-use anchor_lang::{prelude::*, solana_program::message};
+use anchor_lang::{prelude::*, system_program, system_program::System, Discriminator};
 
 declare_id!("31Tq6cGFa1CU8JaU51snTvKaXaKqWP3M3dFBWNXeJqYj");
 
@@ -35,8 +35,7 @@ pub mod voting {
     }
 
 
-    pub fn vote(ctx: Context<Vote>,_poll_id: u64,_candidate:String) -> Result<()>{
-        let candidate = &mut ctx.accounts.candidate_account;
+    pub fn vote(ctx: Context<Vote>,poll_id: u64,_candidate:String) -> Result<()>{
         let current_time = Clock::get()?.unix_timestamp;
         if current_time > (ctx.accounts.poll_account.poll_vote_end as i64) {
             return Err(VotingErrorCode::VotingEnded.into());
@@ -45,7 +44,102 @@ pub mod voting {
             return Err(VotingErrorCode::VotingNotStarted.into());
         }
 
-        candidate.candidate_votes += 1;
+        // voter_receipt是手动创建的（而非#[account(init, ...)]）：这样在它已经
+        // 被本程序初始化过（即该signer已对这场投票投过票）时，我们可以在这里显式
+        // 拒绝并返回VotingErrorCode::AlreadyVoted，而不是让调用方看到Anchor通用的
+        // "account already in use"错误。
+        //
+        // 注意：判断"是否已投票"不能看lamports()==0——receipt的PDA地址是公开可推导
+        // 的，任何人都可以在signer本人投票前，用一笔普通的系统转账把lamports转进这
+        // 个PDA（无需signer签名），从而永久性地把"已有余额"误判成"已投票"，把真正
+        // 的投票者拒之门外。真正能说明"已投票"的是该账户已经被assign给本程序
+        // （owner==本程序id）；只要它还归System Program所有、且没有数据，就说明还
+        // 没被初始化，哪怕已经被预先转入了lamports。
+        let receipt_info = ctx.accounts.voter_receipt.to_account_info();
+        require!(
+            receipt_info.owner == &System::id() && receipt_info.data_len() == 0,
+            VotingErrorCode::AlreadyVoted
+        );
+
+        let space = 8 + VoterReceipt::INIT_SPACE;
+        let poll_id_bytes = poll_id.to_le_bytes();
+        let receipt_seeds: &[&[u8]] = &[
+            b"receipt",
+            poll_id_bytes.as_ref(),
+            ctx.accounts.signer.key.as_ref(),
+            &[ctx.bumps.voter_receipt],
+        ];
+        let signer_seeds: &[&[&[u8]]] = &[receipt_seeds];
+
+        // 和Anchor的`init`约束同样的思路：如果receipt已经被预先转入了lamports，
+        // 直接调用`create_account`会因为账户非空而失败，所以改走"补足免租金差额
+        // + allocate + assign"这条路径；否则走常规的一次性create_account。
+        let current_lamports = receipt_info.lamports();
+        if current_lamports == 0 {
+            let cpi_accounts = system_program::CreateAccount {
+                from: ctx.accounts.signer.to_account_info(),
+                to: receipt_info.clone(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            );
+            system_program::create_account(
+                cpi_ctx,
+                Rent::get()?.minimum_balance(space),
+                space as u64,
+                ctx.program_id,
+            )?;
+        } else {
+            let required_lamports = Rent::get()?
+                .minimum_balance(space)
+                .max(1)
+                .saturating_sub(current_lamports);
+            if required_lamports > 0 {
+                let cpi_accounts = system_program::Transfer {
+                    from: ctx.accounts.signer.to_account_info(),
+                    to: receipt_info.clone(),
+                };
+                let cpi_ctx = CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    cpi_accounts,
+                );
+                system_program::transfer(cpi_ctx, required_lamports)?;
+            }
+
+            let cpi_accounts = system_program::Allocate {
+                account_to_allocate: receipt_info.clone(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            );
+            system_program::allocate(cpi_ctx, space as u64)?;
+
+            let cpi_accounts = system_program::Assign {
+                account_to_assign: receipt_info.clone(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            );
+            system_program::assign(cpi_ctx, ctx.program_id)?;
+        }
+
+        let receipt = VoterReceipt {
+            poll_id,
+            candidate: ctx.accounts.candidate_account.key(),
+            voted_at: current_time,
+        };
+        let mut data = receipt_info.try_borrow_mut_data()?;
+        data[..8].copy_from_slice(&VoterReceipt::DISCRIMINATOR);
+        receipt.serialize(&mut &mut data[8..])?;
+        drop(data);
+
+        ctx.accounts.candidate_account.candidate_votes += 1;
 
         Ok(())
     }
@@ -74,6 +168,19 @@ pub struct Vote<'info>{
     )]
     pub candidate_account: Account<'info,CandidateAccount>,
 
+    /// CHECK: 每个(poll_id, signer)组合只能存在一份receipt，种子里带着signer自己的
+    /// pubkey。这里不用`init`，而是在`vote`里手动检查该PDA是否已经被本程序
+    /// assign（即owner是否还是System Program）——已经assign过就说明重复投票，
+    /// 直接返回`VotingErrorCode::AlreadyVoted`；否则手动CPI创建账户并写入数据
+    /// （兼容PDA被预先转入lamports的情况）。
+    #[account(
+        mut,
+        seeds = [b"receipt", poll_id.to_le_bytes().as_ref(), signer.key().as_ref()],
+        bump
+    )]
+    pub voter_receipt: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
 
 }
 
@@ -151,12 +258,26 @@ pub struct CandidateAccount{
     pub candidate_votes: u64
 }
 
+// 投票回执：每个signer在每场投票里最多只能拥有一份，靠PDA种子+`vote`里对该PDA是否已归本程序所有的手动检查保证（不用init约束）
+#[account]
+#[derive(InitSpace)]
+pub struct VoterReceipt {
+    pub poll_id: u64,
+
+    pub candidate: Pubkey,
+
+    pub voted_at: i64,
+}
+
 #[error_code]
 pub enum VotingErrorCode {
-    
+
     #[msg("Voting has not started yet")]
     VotingNotStarted,
 
     #[msg("Voting has ended")]
-    VotingEnded
+    VotingEnded,
+
+    #[msg("This wallet has already voted in this poll")]
+    AlreadyVoted
 }
\ No newline at end of file